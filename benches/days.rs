@@ -0,0 +1,209 @@
+//! Benchmarks for every day's parser and solvers, on the bundled inputs and on
+//! bigger seeded inputs from the `generate` module.
+//!
+//! Day 1 also compares the speed of the visited sets of the repeat search; the
+//! `memory_on_wide_swings` test in `ex1::visited` checks the memory they use on
+//! the same input.
+//!
+//! Save a baseline with `cargo bench -- --save-baseline <name>` and compare a
+//! later commit against it with `cargo bench -- --baseline <name>`.
+
+#![allow(clippy::needless_return)]
+
+#[macro_use]
+extern crate criterion;
+extern crate advent_of_code_2018;
+
+use advent_of_code_2018::ex1::{self, Backend, Exercise1, Visited};
+use advent_of_code_2018::ex2::{self, Exercise2};
+use advent_of_code_2018::ex3::{self, Exercise3};
+use advent_of_code_2018::ex4::Exercise4;
+use advent_of_code_2018::ex5::{self, Exercise5};
+use advent_of_code_2018::generate::{self, Options};
+use advent_of_code_2018::{Input, Solution};
+use criterion::measurement::WallTime;
+use criterion::{black_box, BenchmarkGroup, BenchmarkId, Criterion};
+use std::collections::LinkedList;
+use std::iter::FromIterator;
+
+fn bundled(day: u8) -> String {
+    Input::bundled(day).read().unwrap()
+}
+
+/// A seeded input of `size` changes, IDs, claims, shifts or units for `day`.
+fn generated(day: u8, size: usize) -> String {
+    let options = Options {
+        seed: 2018,
+        size: Some(size),
+        ..Options::default()
+    };
+    return generate::generate(day, &options).unwrap();
+}
+
+fn bench_parse_and_part_1<S: Solution>(
+    group: &mut BenchmarkGroup<WallTime>,
+    size: &str,
+    content: &str,
+) {
+    group.bench_with_input(BenchmarkId::new("parse", size), content, |b, content| {
+        b.iter(|| S::parse(black_box(content)).unwrap())
+    });
+    let parsed = S::parse(content).unwrap();
+    group.bench_with_input(BenchmarkId::new("part_1", size), &parsed, |b, parsed| {
+        b.iter(|| S::part_1(black_box(parsed)).unwrap())
+    });
+}
+
+fn bench_part_2<S: Solution>(group: &mut BenchmarkGroup<WallTime>, size: &str, content: &str) {
+    let parsed = S::parse(content).unwrap();
+    group.bench_with_input(BenchmarkId::new("part_2", size), &parsed, |b, parsed| {
+        b.iter(|| S::part_2(black_box(parsed)).unwrap())
+    });
+}
+
+fn day_1(c: &mut Criterion) {
+    let content = bundled(1);
+    let mut group = c.benchmark_group("day_1");
+    bench_parse_and_part_1::<Exercise1>(&mut group, "bundled", &content);
+    bench_part_2::<Exercise1>(&mut group, "bundled", &content);
+    let changes = Exercise1::parse(&content).unwrap();
+    group.bench_with_input(
+        BenchmarkId::new("find_repeated_frequency", "bundled"),
+        &changes,
+        |b, changes| b.iter(|| ex1::find_repeated_frequency(0, black_box(changes.clone()))),
+    );
+    for &count in &[10_000, 100_000] {
+        let size = format!("generated_{}", count);
+        let content = generated(1, count);
+        bench_parse_and_part_1::<Exercise1>(&mut group, &size, &content);
+        group.bench_with_input(
+            BenchmarkId::new("part_1_from_reader", &size),
+            &content,
+            |b, content| {
+                b.iter(|| {
+                    Exercise1::part_1_from_reader(&mut black_box(content).as_bytes()).unwrap()
+                })
+            },
+        );
+    }
+    group.finish();
+
+    bench_visited_sets(c);
+}
+
+/// Runs the repeat search over wide frequency changes with every visited set.
+fn bench_visited_sets(c: &mut Criterion) {
+    // Swings of a million drifting by 1 per pass: the search visits two million
+    // frequencies before reaching a million again.
+    let changes = vec![1_000_000, -999_999];
+    let dense = match ex1::choose_backend(0, &changes) {
+        backend @ Backend::Dense { .. } => backend,
+        backend => panic!("expected a dense range, got {:?}", backend),
+    };
+    let mut group = c.benchmark_group("day_1_visited");
+    group.sample_size(10);
+    for &(name, backend) in &[
+        ("dense", dense),
+        ("compressed", Backend::Compressed),
+        ("hash", Backend::Hash),
+    ] {
+        group.bench_with_input(BenchmarkId::new(name, "wide"), &changes, |b, changes| {
+            b.iter(|| {
+                let mut visited = Visited::new(backend);
+                ex1::find_repeated_frequency_with(0, black_box(changes), None, &mut visited)
+            })
+        });
+    }
+    group.finish();
+}
+
+fn day_2(c: &mut Criterion) {
+    let content = bundled(2);
+    let mut group = c.benchmark_group("day_2");
+    bench_parse_and_part_1::<Exercise2>(&mut group, "bundled", &content);
+    bench_part_2::<Exercise2>(&mut group, "bundled", &content);
+    for &count in &[2500, 25_000] {
+        let size = format!("generated_{}", count);
+        bench_parse_and_part_1::<Exercise2>(&mut group, &size, &generated(2, count));
+    }
+    let ids = ex2::parse_ids(&content);
+    for &distance in &[1, 2] {
+        group.bench_with_input(
+            BenchmarkId::new("find_near_duplicates", distance),
+            &ids,
+            |b, ids| b.iter(|| ex2::find_near_duplicates(black_box(ids), distance)),
+        );
+    }
+    group.finish();
+}
+
+fn day_3(c: &mut Criterion) {
+    let content = bundled(3);
+    let mut group = c.benchmark_group("day_3");
+    group.sample_size(10);
+    bench_parse_and_part_1::<Exercise3>(&mut group, "bundled", &content);
+    bench_part_2::<Exercise3>(&mut group, "bundled", &content);
+    for &count in &[1300, 5200] {
+        let content = generated(3, count);
+        let claims = ex3::parse_claims(&content).unwrap();
+        let size = format!("generated_{}", count);
+        bench_parse_and_part_1::<Exercise3>(&mut group, &size, &content);
+        group.bench_with_input(
+            BenchmarkId::new("get_covered_fabric_for_claims", size),
+            &claims,
+            |b, claims| b.iter(|| ex3::get_covered_fabric_for_claims(black_box(claims))),
+        );
+    }
+    group.finish();
+}
+
+fn day_4(c: &mut Criterion) {
+    let content = bundled(4);
+    let mut group = c.benchmark_group("day_4");
+    group.sample_size(10);
+    bench_parse_and_part_1::<Exercise4>(&mut group, "bundled", &content);
+    bench_part_2::<Exercise4>(&mut group, "bundled", &content);
+    for &days in &[3500, 35_000] {
+        let size = format!("generated_{}", days);
+        let content = generated(4, days);
+        bench_parse_and_part_1::<Exercise4>(&mut group, &size, &content);
+        bench_part_2::<Exercise4>(&mut group, &size, &content);
+    }
+    group.finish();
+}
+
+fn day_5(c: &mut Criterion) {
+    let content = bundled(5);
+    let polymer = content.trim();
+    let mut group = c.benchmark_group("day_5");
+    group.sample_size(10);
+    bench_parse_and_part_1::<Exercise5>(&mut group, "bundled", &content);
+    bench_part_2::<Exercise5>(&mut group, "bundled", &content);
+    for &length in &[500_000, 5_000_000] {
+        let size = format!("generated_{}", length);
+        let content = generated(5, length);
+        bench_parse_and_part_1::<Exercise5>(&mut group, &size, &content);
+        bench_part_2::<Exercise5>(&mut group, &size, &content);
+    }
+
+    // Reacting the polymer pair by pair takes seconds on the bundled input.
+    for &length in &[1000, 5000, polymer.len()] {
+        let prefix = &polymer[..length];
+        let size = if length == polymer.len() {
+            String::from("bundled")
+        } else {
+            format!("prefix_{}", length)
+        };
+        group.bench_with_input(
+            BenchmarkId::new("polymer_react", &size),
+            prefix,
+            |b, prefix| {
+                b.iter(|| ex5::polymer_react(LinkedList::from_iter(black_box(prefix).chars())))
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, day_1, day_2, day_3, day_4, day_5);
+criterion_main!(benches);
@@ -0,0 +1,215 @@
+use error::{parse_field, Error, Result};
+use solution::Day;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Identifies a puzzle input by content: the 64-bit FNV-1a hash of the input
+/// with trailing whitespace removed, so a missing final newline doesn't matter.
+pub fn hash_input(content: &str) -> u64 {
+    return content
+        .trim_end()
+        .bytes()
+        .fold(FNV_OFFSET_BASIS, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+        });
+}
+
+/// Known-correct answers, keyed by day, part and input hash.
+///
+/// Stored one answer per line as `<hash> <day> <part> <answer>`, the hash in
+/// hexadecimal and the answer running to the end of the line. Blank lines and
+/// lines starting with `#` are ignored.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Answers {
+    entries: BTreeMap<(u8, u8, u64), String>,
+}
+
+/// The outcome of checking an answer against the store.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String },
+    Unknown,
+}
+
+impl Answers {
+    /// The answers file bundled with the crate, covering `inputs/`.
+    pub fn bundled_path() -> PathBuf {
+        return PathBuf::from(format!("{}/answers.txt", env!("CARGO_MANIFEST_DIR")));
+    }
+
+    pub fn parse(content: &str) -> Result<Answers> {
+        let mut answers = Answers::default();
+        for (index, line) in content.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let entry = parse_entry(line).map_err(|error| error.on_line(index + 1))?;
+            let (hash, day, part, answer) = entry;
+            answers.record(hash, day, part, answer);
+        }
+        return Ok(answers);
+    }
+
+    /// Loads an answers file, a missing file being an empty store.
+    pub fn load(path: &Path) -> Result<Answers> {
+        match fs::read_to_string(path) {
+            Ok(content) => Answers::parse(&content),
+            Err(ref error) if error.kind() == io::ErrorKind::NotFound => Ok(Answers::default()),
+            Err(error) => Err(Error::Io(error)),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        return fs::write(path, self.to_string());
+    }
+
+    pub fn get(&self, hash: u64, day: u8, part: u8) -> Option<&str> {
+        return self.entries.get(&(day, part, hash)).map(String::as_str);
+    }
+
+    /// Stores `answer`, returning the answer it replaces, if any.
+    pub fn record(&mut self, hash: u64, day: u8, part: u8, answer: &str) -> Option<String> {
+        return self
+            .entries
+            .insert((day, part, hash), String::from(answer.trim()));
+    }
+
+    pub fn check(&self, hash: u64, day: u8, part: u8, answer: &str) -> Verdict {
+        match self.get(hash, day, part) {
+            Some(expected) if expected == answer.trim() => Verdict::Pass,
+            Some(expected) => Verdict::Fail {
+                expected: String::from(expected),
+            },
+            None => Verdict::Unknown,
+        }
+    }
+
+    /// Solves `part` of `day` against `content` and checks the answer.
+    pub fn verify(&self, day: &Day, part: u8, content: &str) -> Option<Result<(String, Verdict)>> {
+        let hash = hash_input(content);
+        return day.solve(part, content).map(|result| {
+            result.map(|answer| {
+                let verdict = self.check(hash, day.number, part, &answer);
+                (answer, verdict)
+            })
+        });
+    }
+}
+
+fn parse_entry(line: &str) -> Result<(u64, u8, u8, &str)> {
+    let mut fields = line.splitn(4, ' ');
+    let mut next_field = |expected| {
+        fields
+            .next()
+            .filter(|field| !field.is_empty())
+            .ok_or_else(|| Error::missing(line, expected))
+    };
+
+    let hash_field = next_field("an input hash")?;
+    let hash = u64::from_str_radix(hash_field, 16)
+        .map_err(|_| Error::parse(line, hash_field, "an input hash"))?;
+    let day_field = next_field("a day")?;
+    let day = parse_field(line, day_field, "a day")?;
+    let part_field = next_field("a part")?;
+    let part = parse_field(line, part_field, "a part")?;
+    let answer = next_field("an answer")?.trim();
+    if answer.is_empty() {
+        return Err(Error::missing(line, "an answer"));
+    }
+    return Ok((hash, day, part, answer));
+}
+
+impl fmt::Display for Answers {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "# <input hash> <day> <part> <answer>")?;
+        for (&(day, part, hash), answer) in &self.entries {
+            writeln!(f, "{:016x} {} {} {}", hash, day, part, answer)?;
+        }
+        return Ok(());
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Verdict::Pass => write!(f, "pass"),
+            Verdict::Fail { ref expected } => write!(f, "FAIL (expected {})", expected),
+            Verdict::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use input::Input;
+    use solution::DAYS;
+
+    #[test]
+    fn fnv_hash() {
+        assert_eq!(hash_input(""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(hash_input("a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(hash_input("+1\n-2\n"), hash_input("+1\n-2"));
+    }
+
+    #[test]
+    fn answers_round_trip() {
+        let mut answers = Answers::default();
+        assert_eq!(answers.record(0xab, 1, 2, "42"), None);
+        answers.record(0xab, 3, 1, "ab cd");
+        assert_eq!(answers.record(0xab, 1, 2, "43"), Some(String::from("42")));
+
+        let parsed = Answers::parse(&answers.to_string()).unwrap();
+        assert_eq!(parsed, answers);
+        assert_eq!(parsed.get(0xab, 3, 1), Some("ab cd"));
+        assert_eq!(parsed.check(0xab, 1, 2, "43"), Verdict::Pass);
+        assert_eq!(
+            parsed.check(0xab, 1, 2, "42"),
+            Verdict::Fail {
+                expected: String::from("43")
+            }
+        );
+        assert_eq!(parsed.check(0xab, 1, 1, "43"), Verdict::Unknown);
+    }
+
+    #[test]
+    fn invalid_answers() {
+        match Answers::parse("# comment\n\n00ff 1 x 3\n") {
+            Err(Error::Parse(error)) => {
+                assert_eq!((error.line, error.column), (3, 8));
+                assert_eq!(error.expected, "a part");
+            }
+            result => panic!("unexpected {:?}", result),
+        }
+        match Answers::parse("00ff 1 2") {
+            Err(Error::Parse(error)) => assert_eq!(error.expected, "an answer"),
+            result => panic!("unexpected {:?}", result),
+        }
+    }
+
+    #[test]
+    fn bundled_answers_pass() {
+        let answers = Answers::load(&Answers::bundled_path()).unwrap();
+        for day in DAYS.iter() {
+            let content = Input::bundled(day.number).read().unwrap();
+            for part in 1..3 {
+                let (answer, verdict) = answers.verify(day, part, &content).unwrap().unwrap();
+                assert_eq!(
+                    verdict,
+                    Verdict::Pass,
+                    "day {} part {}: {}",
+                    day.number,
+                    part,
+                    answer
+                );
+            }
+        }
+    }
+}
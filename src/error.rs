@@ -0,0 +1,157 @@
+use std::error;
+use std::fmt;
+use std::io;
+use std::result;
+use std::str::FromStr;
+
+/// Where and why a line of puzzle input could not be parsed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    /// 1-based line number in the input.
+    pub line: usize,
+    /// 1-based column, counted in characters.
+    pub column: usize,
+    /// The offending text, empty when something was missing at the end of the line.
+    pub text: String,
+    /// What the parser expected to find instead.
+    pub expected: &'static str,
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    Parse(ParseError),
+    /// The input parsed but doesn't describe a consistent puzzle.
+    Invalid(String),
+    /// The puzzle has no answer for this input.
+    NoAnswer(&'static str),
+    /// The search gave up after this many steps.
+    BudgetExceeded(u64),
+    /// Applying the change at this 0-based index overflows the frequency.
+    Overflow {
+        index: usize,
+    },
+}
+
+pub type Result<T> = result::Result<T, Error>;
+
+impl Error {
+    /// Builds a parse error for `fragment`, which must be a slice of `line`.
+    pub fn parse(line: &str, fragment: &str, expected: &'static str) -> Error {
+        let offset = fragment.as_ptr() as usize - line.as_ptr() as usize;
+        Error::Parse(ParseError {
+            line: 1,
+            column: line[..offset].chars().count() + 1,
+            text: String::from(fragment),
+            expected,
+        })
+    }
+
+    /// Builds a parse error for something missing at the end of `line`.
+    pub fn missing(line: &str, expected: &'static str) -> Error {
+        Error::parse(line, &line[line.len()..], expected)
+    }
+
+    /// Moves a parse error produced for a single line to its line number in the input.
+    pub fn on_line(self, line_number: usize) -> Error {
+        match self {
+            Error::Parse(error) => Error::Parse(ParseError {
+                line: line_number,
+                ..error
+            }),
+            error => error,
+        }
+    }
+}
+
+/// Parses `field`, a slice of `line`, reporting its position when it isn't a `T`.
+pub fn parse_field<T: FromStr>(line: &str, field: &str, expected: &'static str) -> Result<T> {
+    field
+        .parse()
+        .map_err(|_| Error::parse(line, field, expected))
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.text.is_empty() {
+            write!(
+                f,
+                "line {}, column {}: expected {}, found end of line",
+                self.line, self.column, self.expected
+            )
+        } else {
+            write!(
+                f,
+                "line {}, column {}: expected {}, found `{}`",
+                self.line, self.column, self.expected, self.text
+            )
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Io(ref error) => write!(f, "could not read input: {}", error),
+            Error::Parse(ref error) => write!(f, "invalid input at {}", error),
+            Error::Invalid(ref reason) => write!(f, "inconsistent input: {}", reason),
+            Error::NoAnswer(reason) => write!(f, "no answer: {}", reason),
+            Error::BudgetExceeded(steps) => write!(f, "no answer after {} steps", steps),
+            Error::Overflow { index } => {
+                write!(f, "the frequency overflows at change {}", index + 1)
+            }
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::Io(ref error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Error {
+        Error::Io(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_error_position() {
+        let line = "#1 @ 1,x: 2x2";
+        match Error::parse(line, &line[7..8], "a number").on_line(4) {
+            Error::Parse(error) => assert_eq!(
+                ParseError {
+                    line: 4,
+                    column: 8,
+                    text: String::from("x"),
+                    expected: "a number",
+                },
+                error
+            ),
+            error => panic!("unexpected error {}", error),
+        }
+    }
+
+    #[test]
+    fn parse_error_display() {
+        let line = "+1 +é";
+        assert_eq!(
+            "invalid input at line 1, column 4: expected a number, found `+é`",
+            parse_field::<i32>(line, &line[3..], "a number")
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(
+            "invalid input at line 2, column 3: expected a number, found end of line",
+            Error::missing("+1", "a number").on_line(2).to_string()
+        );
+    }
+}
@@ -0,0 +1,661 @@
+use error::{Error, Result};
+use input::Input;
+use solution::Solution;
+use std::collections::HashSet;
+use std::fmt;
+use std::io::BufRead;
+use std::ops::RangeInclusive;
+
+mod parse;
+mod tracker;
+mod trajectory;
+mod vector;
+mod visited;
+
+pub use self::parse::{
+    from_string_to_frequencies, parse_change, parse_changes, parse_changes_with, read_changes,
+    read_changes_with, Changes, ParseMode, ParsedChanges,
+};
+pub use self::tracker::{FrequencyTracker, Repeat};
+pub use self::trajectory::{
+    analyze_trajectory, repeated_frequencies, write_trajectory_csv, Extreme, RepeatedFrequencies,
+    TrajectoryReport,
+};
+pub use self::vector::{first_vector_repeat, parse_vector_changes, sum_vectors, VectorRepeat};
+pub use self::visited::{
+    choose_backend, Backend, CompressedBitmap, DenseBitSet, Visited, VisitedSet,
+};
+
+/// An integer type frequencies can be accumulated in without wrapping around.
+pub trait Frequency: Copy + fmt::Debug + fmt::Display + PartialEq {
+    /// Adds `change`, or returns `None` if the result doesn't fit.
+    fn add_change(self, change: Self) -> Option<Self>;
+    /// Parses `src` in the given radix, an optional sign included.
+    fn from_str_radix(src: &str, radix: u32) -> Option<Self>;
+}
+
+macro_rules! impl_frequency {
+    ($($int:ty),*) => {
+        $(
+            impl Frequency for $int {
+                fn add_change(self, change: $int) -> Option<$int> {
+                    self.checked_add(change)
+                }
+
+                fn from_str_radix(src: &str, radix: u32) -> Option<$int> {
+                    <$int>::from_str_radix(src, radix).ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_frequency!(i32, i64, i128);
+
+/// Applies every change to `initial_frequency` in order, failing with
+/// `Error::Overflow` at the first change whose result doesn't fit in `F`.
+pub fn accumulate<F: Frequency, C: Copy + Into<F>>(
+    initial_frequency: F,
+    changes: &[C],
+) -> Result<F> {
+    let mut frequency = initial_frequency;
+    for (index, &change) in changes.iter().enumerate() {
+        frequency = frequency
+            .add_change(change.into())
+            .ok_or(Error::Overflow { index })?;
+    }
+    return Ok(frequency);
+}
+
+/// Applies every frequency change to `initial_frequency` and returns the result.
+pub fn generate_frequency(initial_frequency: i32, input: Vec<i32>) -> Result<i32> {
+    return accumulate(initial_frequency, &input);
+}
+
+/// The frequency after each change, see `running_frequencies`.
+pub struct RunningFrequencies<I, F> {
+    changes: I,
+    frequency: F,
+    index: usize,
+    done: bool,
+}
+
+/// Lazily applies each change to `initial_frequency`, yielding every frequency
+/// reached. Stops after the first error, `Error::Overflow` included.
+pub fn running_frequencies<F, C, I>(
+    initial_frequency: F,
+    changes: I,
+) -> RunningFrequencies<I::IntoIter, F>
+where
+    F: Frequency,
+    C: Into<F>,
+    I: IntoIterator<Item = Result<C>>,
+{
+    return RunningFrequencies {
+        changes: changes.into_iter(),
+        frequency: initial_frequency,
+        index: 0,
+        done: false,
+    };
+}
+
+impl<F, C, I> Iterator for RunningFrequencies<I, F>
+where
+    F: Frequency,
+    C: Into<F>,
+    I: Iterator<Item = Result<C>>,
+{
+    type Item = Result<F>;
+
+    fn next(&mut self) -> Option<Result<F>> {
+        if self.done {
+            return None;
+        }
+        let index = self.index;
+        let frequency = self.frequency;
+        let next = self.changes.next()?.and_then(|change| {
+            frequency
+                .add_change(change.into())
+                .ok_or(Error::Overflow { index })
+        });
+        self.index += 1;
+        match next {
+            Ok(frequency) => self.frequency = frequency,
+            Err(_) => self.done = true,
+        }
+        return Some(next);
+    }
+}
+
+/// Streams the changes from `reader` and returns the frequency they end on,
+/// starting from 0, in constant memory.
+pub fn final_frequency<R: BufRead>(reader: R) -> Result<i64> {
+    return running_frequencies(0, read_changes::<R, i32>(reader))
+        .try_fold(0, |_, frequency| frequency);
+}
+
+/// Runs `process` over the frequency changes of `input`, starting from 0.
+pub fn solve_exercise<T>(input: &Input, process: fn(i32, Vec<i32>) -> T) -> Result<T> {
+    let reader = input.open()?;
+
+    let input = read_changes(reader).collect::<Result<Vec<i32>>>()?;
+
+    return Ok(process(0, input));
+}
+
+/// Whether cycling through the changes ever reaches a frequency twice.
+///
+/// Without drift the first frequency comes back after each pass. Otherwise
+/// every pass shifts the frequencies by the drift, so two of them can only
+/// meet if they're congruent modulo the drift.
+pub fn can_repeat(changes: &[i32]) -> bool {
+    let drift: i64 = changes.iter().map(|&change| i64::from(change)).sum();
+    if changes.is_empty() || drift == 0 {
+        return !changes.is_empty();
+    }
+    let mut residues: HashSet<i64> = HashSet::new();
+    let mut frequency: i64 = 0;
+    for &change in changes {
+        if !residues.insert(frequency.rem_euclid(drift.abs())) {
+            return true;
+        }
+        frequency += i64::from(change);
+    }
+    return false;
+}
+
+/// Returns the first frequency reached twice while cycling through the changes,
+/// or `None` when no frequency ever repeats.
+///
+/// Panics if a frequency doesn't fit in an `i32`, see `find_repeated_frequency_within`.
+pub fn find_repeated_frequency(base_frequency: i32, input: Vec<i32>) -> Option<i32> {
+    return find_repeated_frequency_within(base_frequency, &input, None)
+        .unwrap_or_else(|error| panic!("{}", error));
+}
+
+/// Like `find_repeated_frequency`, giving up with `Error::BudgetExceeded` once
+/// `budget` changes were applied without finding a repeat. Fails with
+/// `Error::Overflow` if a frequency doesn't fit in an `i32`, its index being
+/// the position of the change in `input`.
+pub fn find_repeated_frequency_within(
+    base_frequency: i32,
+    input: &[i32],
+    budget: Option<u64>,
+) -> Result<Option<i32>> {
+    let mut visited = Visited::for_changes(i64::from(base_frequency), input);
+    return find_repeated_frequency_with(base_frequency, input, budget, &mut visited);
+}
+
+/// Like `find_repeated_frequency_within`, remembering the visited frequencies
+/// in `visited`, which must be able to hold every frequency reached.
+pub fn find_repeated_frequency_with<V: VisitedSet>(
+    base_frequency: i32,
+    input: &[i32],
+    budget: Option<u64>,
+    visited: &mut V,
+) -> Result<Option<i32>> {
+    if !can_repeat(input) {
+        return Ok(None);
+    }
+    // My naive implementation used a Vec. 360s -> 2s
+    let mut current_frequency = base_frequency;
+    visited.visit(i64::from(current_frequency));
+    let mut steps: u64 = 0;
+    loop {
+        for (index, &frequency) in input.iter().enumerate() {
+            if budget.is_some_and(|budget| steps >= budget) {
+                return Err(Error::BudgetExceeded(steps));
+            }
+            steps += 1;
+            current_frequency = current_frequency
+                .checked_add(frequency)
+                .ok_or(Error::Overflow { index })?;
+            if visited.visit(i64::from(current_frequency)) {
+                return Ok(Some(current_frequency));
+            }
+        }
+    }
+}
+
+/// A frequency reached for the second time, and when.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FirstRepeat {
+    pub frequency: i64,
+    /// How many full passes through the changes were made before, starting at 0.
+    pub cycle: u64,
+    /// Index of the change that reaches the frequency again.
+    pub position: usize,
+    /// How many changes were applied in total, the repeating one included.
+    pub step: u128,
+}
+
+/// Finds the first frequency reached twice while cycling through the changes
+/// from 0, or `None` when it provably never happens.
+///
+/// After `m` passes, the frequency reached before change `i` is `s[i] + m * drift`,
+/// `s` being the prefix sums and `drift` the sum of all changes. Two prefix sums
+/// can only meet if they are congruent modulo the drift, in which case the
+/// lower one (in the direction of the drift) catches up with the next one in
+/// `(s[j] - s[i]) / drift` passes. Sorting each residue class is then enough to
+/// find the earliest meeting, in O(n log n).
+pub fn first_repeat(changes: &[i32]) -> Option<FirstRepeat> {
+    let length = changes.len();
+    let mut sums: Vec<i64> = Vec::with_capacity(length);
+    let mut drift: i64 = 0;
+    for &change in changes {
+        sums.push(drift);
+        drift += i64::from(change);
+    }
+
+    // The repeat happens after `step` changes, reaching `frequency` again.
+    let (step, frequency) = if drift == 0 {
+        first_repeat_without_drift(&sums)?
+    } else {
+        first_repeat_with_drift(&sums, drift)?
+    };
+    return Some(repeat_at(frequency, step, length));
+}
+
+/// The first repeat when cycling through the changes from `start` instead of 0.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StartRepeat {
+    pub start: i64,
+    pub repeat: Option<FirstRepeat>,
+}
+
+/// The first repeats for a range of starting frequencies, see `first_repeats_from`.
+pub struct StartRepeats {
+    from_zero: Option<FirstRepeat>,
+    starts: RangeInclusive<i64>,
+}
+
+/// Lazily finds the first repeat for every starting frequency in `starts`.
+///
+/// Starting elsewhere shifts every frequency reached by the same amount, so
+/// the first repeat happens at the same step, shifted by the start: it's only
+/// searched for once. Yields `Error::Invalid` for a start whose shifted repeat
+/// doesn't fit in an `i64`.
+pub fn first_repeats_from(changes: &[i32], starts: RangeInclusive<i64>) -> StartRepeats {
+    return StartRepeats {
+        from_zero: first_repeat(changes),
+        starts,
+    };
+}
+
+impl Iterator for StartRepeats {
+    type Item = Result<StartRepeat>;
+
+    fn next(&mut self) -> Option<Result<StartRepeat>> {
+        let start = self.starts.next()?;
+        let repeat = match self.from_zero {
+            Some(repeat) => match repeat.frequency.checked_add(start) {
+                Some(frequency) => Some(FirstRepeat {
+                    frequency,
+                    ..repeat
+                }),
+                None => {
+                    return Some(Err(Error::Invalid(format!(
+                        "the first repeat overflows when starting from {}",
+                        start
+                    ))))
+                }
+            },
+            None => None,
+        };
+        return Some(Ok(StartRepeat { start, repeat }));
+    }
+}
+
+/// The repeat of `frequency` after `step` changes out of `length` per pass.
+fn repeat_at(frequency: i64, step: u128, length: usize) -> FirstRepeat {
+    let length = length as u128;
+    return FirstRepeat {
+        frequency,
+        cycle: ((step - 1) / length) as u64,
+        position: ((step - 1) % length) as usize,
+        step,
+    };
+}
+
+/// Without drift every pass is the same, so the first one either repeats a
+/// frequency or the second one starts by repeating 0.
+fn first_repeat_without_drift(sums: &[i64]) -> Option<(u128, i64)> {
+    let mut seen: HashSet<i64> = HashSet::new();
+    for (index, &sum) in sums.iter().enumerate() {
+        if !seen.insert(sum) {
+            return Some((index as u128, sum));
+        }
+    }
+    return sums.first().map(|&first| (sums.len() as u128, first));
+}
+
+fn first_repeat_with_drift(sums: &[i64], drift: i64) -> Option<(u128, i64)> {
+    let modulus = drift.abs();
+    let direction = drift.signum();
+    // Ordered by residue, then by progress along the drift, then by position.
+    let mut classes: Vec<(i64, i64, usize)> = sums
+        .iter()
+        .enumerate()
+        .map(|(index, &sum)| (sum.rem_euclid(modulus), sum * direction, index))
+        .collect();
+    classes.sort_unstable();
+
+    let length = sums.len() as u128;
+    let first = classes
+        .windows(2)
+        .filter(|pair| pair[0].0 == pair[1].0)
+        .map(|pair| {
+            let (_, progress, index) = pair[0];
+            let (_, next_progress, next_index) = pair[1];
+            if progress == next_progress {
+                // Both are reached in the first pass.
+                (next_index as u128, sums[index])
+            } else {
+                let passes = ((next_progress - progress) / modulus) as u128;
+                (passes * length + index as u128, sums[next_index])
+            }
+        })
+        .min_by_key(|&(step, _)| step);
+    return first;
+}
+
+/// Day 1: Chronal Calibration.
+pub struct Exercise1;
+
+impl Solution for Exercise1 {
+    type Parsed<'a> = Vec<i32>;
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    fn parse(content: &str) -> Result<Vec<i32>> {
+        from_string_to_frequencies(content)
+    }
+
+    fn part_1(frequencies: &Vec<i32>) -> Result<i64> {
+        accumulate(0, frequencies)
+    }
+
+    fn part_2(frequencies: &Vec<i32>) -> Result<i64> {
+        first_repeat(frequencies)
+            .map(|repeat| repeat.frequency)
+            .ok_or(Error::NoAnswer("no frequency is ever reached twice"))
+    }
+
+    fn part_1_from_reader(reader: &mut dyn BufRead) -> Result<i64> {
+        final_frequency(reader)
+    }
+
+    /// Only the changes themselves are kept, as they're needed for cycling.
+    fn part_2_from_reader(reader: &mut dyn BufRead) -> Result<i64> {
+        Exercise1::part_2(&read_changes(reader).collect::<Result<Vec<i32>>>()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use std::collections::HashSet;
+
+    /// Cycles through the changes for at most `max_steps`, like `find_repeated_frequency`.
+    fn simulate_repeat(changes: &[i32], max_steps: u128) -> Option<(u128, i64)> {
+        let mut seen: HashSet<i64> = HashSet::new();
+        let mut frequency: i64 = 0;
+        seen.insert(frequency);
+        let mut step = 0;
+        while step < max_steps && !changes.is_empty() {
+            frequency += i64::from(changes[(step % changes.len() as u128) as usize]);
+            step += 1;
+            if !seen.insert(frequency) {
+                return Some((step, frequency));
+            }
+        }
+        return None;
+    }
+
+    #[test]
+    fn generate_frequency_adds() {
+        assert_eq!(4, generate_frequency(0, vec![-1, 2, 3]).unwrap());
+        assert_eq!(3, generate_frequency(0, vec![1, -2, 3, 1]).unwrap());
+    }
+
+    #[test]
+    fn accumulate_reports_overflow() {
+        let changes = [1, i32::MAX - 1, 1, -5];
+        match generate_frequency(0, changes.to_vec()) {
+            Err(Error::Overflow { index: 2 }) => {}
+            result => panic!("unexpected result {:?}", result),
+        }
+        assert_eq!(
+            i64::from(i32::MAX) - 4,
+            accumulate::<i64, i32>(0, &changes).unwrap()
+        );
+        match accumulate::<i32, i32>(i32::MIN, &[0, -1]) {
+            Err(Error::Overflow { index: 1 }) => {}
+            result => panic!("unexpected result {:?}", result),
+        }
+
+        let huge: Vec<i64> = vec![i64::MAX, i64::MAX, -1];
+        assert_eq!(
+            2 * i128::from(i64::MAX) - 1,
+            accumulate::<i128, i64>(0, &huge).unwrap()
+        );
+        match accumulate::<i64, i64>(0, &huge) {
+            Err(Error::Overflow { index: 1 }) => {}
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+
+    #[test]
+    fn accumulate_long_input() {
+        let changes = vec![1i32; 1_000_000];
+        assert_eq!(1_000_000, generate_frequency(0, changes).unwrap());
+    }
+
+    #[test]
+    fn find_repeated_frequency_works() {
+        assert_eq!(Some(0), find_repeated_frequency(0, vec![1, -1]));
+        assert_eq!(Some(10), find_repeated_frequency(0, vec![3, 3, 4, -2, -4]));
+    }
+
+    #[test]
+    fn find_repeated_frequency_terminates() {
+        assert_eq!(None, find_repeated_frequency(0, vec![1, 1]));
+        assert_eq!(None, find_repeated_frequency(0, vec![]));
+        assert_eq!(None, find_repeated_frequency(0, vec![2, -1, 3]));
+        assert_eq!(Some(2), find_repeated_frequency(0, vec![2, -1, 1]));
+        assert!(can_repeat(&[0]));
+        assert!(!can_repeat(&[-3, 1]));
+    }
+
+    #[test]
+    fn find_repeated_frequency_budget() {
+        let changes = [3, 3, 4, -2, -4];
+        assert_eq!(
+            Some(10),
+            find_repeated_frequency_within(0, &changes, Some(7)).unwrap()
+        );
+        match find_repeated_frequency_within(0, &changes, Some(6)) {
+            Err(Error::BudgetExceeded(6)) => {}
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+
+    #[test]
+    fn first_repeats_from_starts() {
+        let table: Vec<(i64, i64, u128)> = first_repeats_from(&[3, 3, 4, -2, -4], -2..=1)
+            .map(|row| {
+                let row = row.unwrap();
+                let repeat = row.repeat.unwrap();
+                (row.start, repeat.frequency, repeat.step)
+            })
+            .collect();
+        assert_eq!(vec![(-2, 8, 7), (-1, 9, 7), (0, 10, 7), (1, 11, 7)], table);
+
+        let never: Vec<StartRepeat> = first_repeats_from(&[1, 1], 5..=6)
+            .collect::<Result<_>>()
+            .unwrap();
+        assert!(never.iter().all(|row| row.repeat.is_none()));
+        assert_eq!(2, never.len());
+
+        let mut wide = first_repeats_from(&[1, 0, -1], i64::MAX - 1..=i64::MAX);
+        assert_eq!(
+            i64::MAX,
+            wide.next().unwrap().unwrap().repeat.unwrap().frequency
+        );
+        match wide.next() {
+            Some(Err(Error::Invalid(message))) => assert_eq!(
+                format!("the first repeat overflows when starting from {}", i64::MAX),
+                message
+            ),
+            result => panic!("unexpected result {:?}", result),
+        }
+        // Only the starts looked at are computed.
+        assert_eq!(
+            3,
+            first_repeats_from(&[1, -1], 0..=i64::MAX).take(3).count()
+        );
+    }
+
+    #[test]
+    fn find_repeated_frequency_overflow() {
+        match find_repeated_frequency_within(i32::MAX - 1, &[1, 1, -3], None) {
+            Err(Error::Overflow { index: 1 }) => {}
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+
+    #[test]
+    fn first_repeat_examples() {
+        assert_eq!(
+            first_repeat(&[1, -1]),
+            Some(FirstRepeat {
+                frequency: 0,
+                cycle: 0,
+                position: 1,
+                step: 2
+            })
+        );
+        assert_eq!(
+            first_repeat(&[3, 3, 4, -2, -4]),
+            Some(FirstRepeat {
+                frequency: 10,
+                cycle: 1,
+                position: 1,
+                step: 7
+            })
+        );
+        assert_eq!(first_repeat(&[-6, 3, 8, 5, -6]).unwrap().frequency, 5);
+        assert_eq!(first_repeat(&[7, 7, -2, -7, -4]).unwrap().frequency, 14);
+        assert_eq!(first_repeat(&[1, 1, 1]), None);
+        assert_eq!(first_repeat(&[]), None);
+    }
+
+    #[test]
+    fn stream_changes() {
+        let reader = "+1 -2\n\n  +3\r\n+1".as_bytes();
+        let frequencies: Vec<i64> = running_frequencies(0, read_changes::<_, i32>(reader))
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(vec![1, -1, 2, 3], frequencies);
+        assert_eq!(3, final_frequency("+1 -2\n+3 +1\n".as_bytes()).unwrap());
+        assert_eq!(0, final_frequency("".as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn stream_changes_lazily() {
+        let reader = "+1\n+2\n+x\n".as_bytes();
+        let mut frequencies = running_frequencies(0, read_changes::<_, i32>(reader));
+        assert_eq!(1, frequencies.next().unwrap().unwrap());
+        assert_eq!(3, frequencies.next().unwrap().unwrap());
+        match frequencies.next() {
+            Some(Err(Error::Parse(error))) => {
+                assert_eq!((3, 1, "+x"), (error.line, error.column, &*error.text))
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
+        assert!(frequencies.next().is_none());
+
+        let invalid_utf8: &[u8] = b"+1\n\xff\n";
+        let changes: Vec<Result<i32>> = read_changes(invalid_utf8).collect();
+        match changes.as_slice() {
+            [Ok(1), Err(Error::Io(_))] => {}
+            result => panic!("unexpected result {:?}", result),
+        }
+
+        let overflowing = running_frequencies(i32::MAX - 1, vec![Ok(1), Ok(1), Ok(1)]);
+        match overflowing.collect::<Vec<Result<i32>>>().as_slice() {
+            [Ok(_), Err(Error::Overflow { index: 1 })] => {}
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+
+    #[test]
+    fn exercise_1() {
+        assert_eq!(
+            574,
+            solve_exercise(&Input::bundled(1), generate_frequency)
+                .unwrap()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn exercise_2() {
+        assert_eq!(
+            Some(452),
+            solve_exercise(&Input::bundled(1), find_repeated_frequency).unwrap()
+        );
+        assert_eq!(452, Exercise1::solve_part_2(&Input::bundled(1)).unwrap());
+    }
+
+    proptest! {
+        #[test]
+        fn first_repeat_matches_simulation(changes in prop::collection::vec(-20i32..20, 0..30)) {
+            let max_steps = 10_000;
+            match first_repeat(&changes) {
+                Some(repeat) if repeat.step <= max_steps => {
+                    prop_assert_eq!(
+                        simulate_repeat(&changes, max_steps),
+                        Some((repeat.step, repeat.frequency))
+                    );
+                    prop_assert_eq!(
+                        repeat.step,
+                        u128::from(repeat.cycle) * changes.len() as u128 + repeat.position as u128 + 1
+                    );
+                }
+                _ => prop_assert_eq!(simulate_repeat(&changes, max_steps), None),
+            }
+        }
+
+        #[test]
+        fn first_repeats_from_match_search(
+            changes in prop::collection::vec(-20i32..20, 1..20),
+            start in -1000i32..1000,
+        ) {
+            let row = first_repeats_from(&changes, i64::from(start)..=i64::from(start))
+                .next()
+                .unwrap()
+                .unwrap();
+            let repeat = row.repeat.filter(|repeat| repeat.step <= 10_000);
+            if row.repeat.is_none() || repeat.is_some() {
+                prop_assert_eq!(
+                    find_repeated_frequency(start, changes.clone()).map(i64::from),
+                    repeat.map(|repeat| repeat.frequency)
+                );
+            }
+        }
+
+        #[test]
+        fn search_stops_when_no_repeat(changes in prop::collection::vec(-20i32..20, 0..30)) {
+            let repeat = first_repeat(&changes);
+            prop_assert_eq!(can_repeat(&changes), repeat.is_some());
+            if repeat.is_none_or(|repeat| repeat.step <= 10_000) {
+                prop_assert_eq!(
+                    find_repeated_frequency(0, changes.clone()).map(i64::from),
+                    repeat.map(|repeat| repeat.frequency)
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,364 @@
+use super::Frequency;
+use error::{Error, ParseError, Result};
+use std::io::{self, BufRead};
+use std::marker::PhantomData;
+use std::mem;
+use std::str;
+
+const FREQUENCY_CHANGE: &str = "a frequency change such as +7, -3 or +0x1f";
+
+/// How to deal with tokens that aren't frequency changes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParseMode {
+    /// Fail on the first invalid token.
+    Strict,
+    /// Skip invalid tokens, reporting each of them.
+    Lenient,
+}
+
+/// Frequency changes parsed in lenient mode, along with the tokens skipped.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedChanges<F> {
+    pub changes: Vec<F>,
+    pub skipped: Vec<ParseError>,
+}
+
+/// Parses `token`, a slice of `line`, as a single frequency change: an optional
+/// sign followed by decimal digits, or by hexadecimal digits after `0x`.
+pub fn parse_change<F: Frequency>(line: &str, token: &str) -> Result<F> {
+    let (sign, unsigned) = match token.as_bytes().first() {
+        Some(b'+') => ("", &token[1..]),
+        Some(b'-') => ("-", &token[1..]),
+        _ => ("", token),
+    };
+    let hex = unsigned
+        .strip_prefix("0x")
+        .or_else(|| unsigned.strip_prefix("0X"));
+    let change = match hex {
+        Some(digits) if digits.chars().all(|digit| digit.is_ascii_hexdigit()) => {
+            F::from_str_radix(&format!("{}{}", sign, digits), 16)
+        }
+        None if unsigned.chars().all(|digit| digit.is_ascii_digit()) => {
+            F::from_str_radix(token, 10)
+        }
+        _ => None,
+    };
+    return change.ok_or_else(|| Error::parse(line, token, FREQUENCY_CHANGE));
+}
+
+/// Parses frequency changes such as `+7` or `-3`, see `read_changes`.
+pub fn from_string_to_frequencies(content: &str) -> Result<Vec<i32>> {
+    return parse_changes(content);
+}
+
+/// Parses frequency changes, each of which must fit in `F`, see `read_changes`.
+pub fn parse_changes<F: Frequency>(content: &str) -> Result<Vec<F>> {
+    return read_changes(content.as_bytes()).collect();
+}
+
+/// Parses frequency changes in the given mode. Strict mode never skips anything.
+pub fn parse_changes_with<F: Frequency>(
+    content: &str,
+    mode: ParseMode,
+) -> Result<ParsedChanges<F>> {
+    let mut parsed = ParsedChanges {
+        changes: Vec::new(),
+        skipped: Vec::new(),
+    };
+    for change in read_changes_with(content.as_bytes(), mode) {
+        match change {
+            Ok(change) => parsed.changes.push(change),
+            Err(Error::Parse(error)) if mode == ParseMode::Lenient => parsed.skipped.push(error),
+            Err(error) => return Err(error),
+        }
+    }
+    return Ok(parsed);
+}
+
+/// Frequency changes parsed one at a time from a reader, see `read_changes`.
+pub struct Changes<R, F> {
+    reader: R,
+    mode: ParseMode,
+    tokenizer: Tokenizer,
+    done: bool,
+    frequency: PhantomData<F>,
+}
+
+/// Lazily parses frequency changes from `reader` in strict mode, only keeping
+/// the current change in memory, however long the lines are. Stops after the
+/// first error.
+///
+/// Changes are separated by whitespace or commas, and `#` starts a comment
+/// running to the end of the line.
+pub fn read_changes<R: BufRead, F: Frequency>(reader: R) -> Changes<R, F> {
+    return read_changes_with(reader, ParseMode::Strict);
+}
+
+/// Like `read_changes`. In lenient mode, invalid tokens are yielded as parse
+/// errors but reading carries on after them.
+pub fn read_changes_with<R: BufRead, F: Frequency>(reader: R, mode: ParseMode) -> Changes<R, F> {
+    return Changes {
+        reader,
+        mode,
+        tokenizer: Tokenizer {
+            token: Vec::new(),
+            char_start: 0,
+            start: (1, 1),
+            position: (1, 1),
+            in_comment: false,
+        },
+        done: false,
+        frequency: PhantomData,
+    };
+}
+
+fn is_separator(c: char) -> bool {
+    return c == ',' || c.is_whitespace();
+}
+
+/// Splits the input into tokens one byte at a time, so a token can span
+/// several reads.
+struct Tokenizer {
+    token: Vec<u8>,
+    /// Byte offset in `token` of its last character.
+    char_start: usize,
+    /// Line and column where `token` starts.
+    start: (usize, usize),
+    /// Line and column of the next byte.
+    position: (usize, usize),
+    in_comment: bool,
+}
+
+impl Tokenizer {
+    /// Feeds the next byte, returning whether it ends a token.
+    fn push(&mut self, byte: u8) -> bool {
+        let position = self.position;
+        let starts_char = byte & 0xC0 != 0x80;
+        if byte == b'\n' {
+            self.position = (position.0 + 1, 1);
+        } else if starts_char {
+            self.position.1 += 1;
+        }
+        if self.in_comment {
+            self.in_comment = byte != b'\n';
+            return false;
+        }
+        if byte == b'#' {
+            self.in_comment = true;
+            return !self.token.is_empty();
+        }
+        if byte.is_ascii() && is_separator(char::from(byte)) {
+            return !self.token.is_empty();
+        }
+
+        if self.token.is_empty() {
+            self.start = position;
+        }
+        if starts_char {
+            self.char_start = self.token.len();
+        }
+        self.token.push(byte);
+        // Non-ASCII whitespace separates tokens too, once all its bytes are in.
+        let separator = !byte.is_ascii()
+            && str::from_utf8(&self.token[self.char_start..])
+                .ok()
+                .and_then(|c| c.chars().next())
+                .is_some_and(is_separator);
+        if separator {
+            self.token.truncate(self.char_start);
+            return !self.token.is_empty();
+        }
+        return false;
+    }
+
+    /// Takes the token read so far, with the line and column it starts at.
+    fn take(&mut self) -> (Vec<u8>, (usize, usize)) {
+        self.char_start = 0;
+        return (mem::take(&mut self.token), self.start);
+    }
+}
+
+impl<R: BufRead, F: Frequency> Changes<R, F> {
+    fn parse_token(&mut self) -> Result<F> {
+        let (token, (line, column)) = self.tokenizer.take();
+        let change = match String::from_utf8(token) {
+            Ok(token) => parse_change(&token, &token).map_err(|error| match error {
+                Error::Parse(error) => Error::Parse(ParseError {
+                    line,
+                    column,
+                    ..error
+                }),
+                error => error,
+            }),
+            Err(_) => Err(Error::Io(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "stream did not contain valid UTF-8",
+            ))),
+        };
+        self.done |= match change {
+            Ok(_) => false,
+            Err(Error::Parse(_)) => self.mode == ParseMode::Strict,
+            Err(_) => true,
+        };
+        return change;
+    }
+}
+
+impl<R: BufRead, F: Frequency> Iterator for Changes<R, F> {
+    type Item = Result<F>;
+
+    fn next(&mut self) -> Option<Result<F>> {
+        while !self.done {
+            let (consumed, token_ended) = match self.reader.fill_buf() {
+                Ok([]) => {
+                    self.done = true;
+                    (0, !self.tokenizer.token.is_empty())
+                }
+                Ok(buffer) => {
+                    let tokenizer = &mut self.tokenizer;
+                    match buffer.iter().position(|&byte| tokenizer.push(byte)) {
+                        Some(index) => (index + 1, true),
+                        None => (buffer.len(), false),
+                    }
+                }
+                Err(ref error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => {
+                    self.done = true;
+                    return Some(Err(Error::Io(error)));
+                }
+            };
+            self.reader.consume(consumed);
+            if token_ended {
+                return Some(self.parse_token());
+            }
+        }
+        return None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_frequencies() {
+        assert_eq!(
+            vec![1, -2, 3],
+            from_string_to_frequencies("+1\n-2 +3\n").unwrap()
+        );
+        match from_string_to_frequencies("+1\n-2 +3x\n") {
+            Err(Error::Parse(error)) => {
+                assert_eq!((2, 4, "+3x"), (error.line, error.column, &*error.text))
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+
+    #[test]
+    fn parse_wide_changes() {
+        assert_eq!(
+            vec![i128::from(i64::MAX) + 1, -3],
+            parse_changes::<i128>("+9223372036854775808 -3").unwrap()
+        );
+        match parse_changes::<i32>("+1\n+2147483648\n") {
+            Err(Error::Parse(error)) => assert_eq!((2, 1), (error.line, error.column)),
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+
+    #[test]
+    fn parse_device_dump() {
+        let dump = "# calibration run 3\n+1, -2,+0x1F\n7 , -0X0a # drift check\n\n,,-3,\n";
+        assert_eq!(
+            vec![1, -2, 31, 7, -10, -3],
+            parse_changes::<i32>(dump).unwrap()
+        );
+        assert_eq!(
+            vec![i32::MIN, i32::MAX],
+            parse_changes::<i32>("-0x80000000 +0x7fffffff").unwrap()
+        );
+    }
+
+    #[test]
+    fn strict_mode_rejects_garbage() {
+        for &(content, column, text) in &[
+            ("+1, +-2", 5, "+-2"),
+            ("+1, 0x", 5, "0x"),
+            ("+1, +0x+5", 5, "+0x+5"),
+            ("+1, +", 5, "+"),
+            ("+1, 0xfg", 5, "0xfg"),
+            ("+1, +0x80000000", 5, "+0x80000000"),
+            ("+1, é3", 5, "é3"),
+        ] {
+            match parse_changes_with::<i32>(content, ParseMode::Strict) {
+                Err(Error::Parse(error)) => {
+                    assert_eq!((1, column, text), (error.line, error.column, &*error.text))
+                }
+                result => panic!("unexpected result {:?} for {}", result, content),
+            }
+        }
+    }
+
+    /// An endless line of `+1,` changes.
+    struct Endless(usize);
+
+    impl io::Read for Endless {
+        fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+            for byte in buffer.iter_mut() {
+                *byte = b"+1,"[self.0 % 3];
+                self.0 += 1;
+            }
+            return Ok(buffer.len());
+        }
+    }
+
+    #[test]
+    fn read_changes_from_a_single_endless_line() {
+        let changes = read_changes::<_, i32>(io::BufReader::with_capacity(16, Endless(0)));
+        let sum: i32 = changes.take(100_000).map(Result::unwrap).sum();
+        assert_eq!(100_000, sum);
+    }
+
+    #[test]
+    fn read_changes_across_small_reads() {
+        let dump = "# calibré\n+1, -2,+0x1F\u{a0}7\n -0X0a # drift\n+1, é3, +abc\n";
+        for &capacity in &[1, 2, 3, 64] {
+            let reader = io::BufReader::with_capacity(capacity, dump.as_bytes());
+            let changes: Vec<Result<i32>> = read_changes_with(reader, ParseMode::Lenient).collect();
+            let changes: Vec<_> = changes
+                .into_iter()
+                .map(|change| match change {
+                    Ok(change) => Ok(change),
+                    Err(Error::Parse(error)) => Err((error.line, error.column, error.text)),
+                    Err(error) => panic!("unexpected error {:?}", error),
+                })
+                .collect();
+            assert_eq!(
+                vec![
+                    Ok(1),
+                    Ok(-2),
+                    Ok(31),
+                    Ok(7),
+                    Ok(-10),
+                    Ok(1),
+                    Err((4, 5, String::from("é3"))),
+                    Err((4, 9, String::from("+abc"))),
+                ],
+                changes
+            );
+        }
+    }
+
+    #[test]
+    fn lenient_mode_reports_skipped_tokens() {
+        let parsed =
+            parse_changes_with::<i32>("+1, oops, -2\n# +x\nzz +0x10", ParseMode::Lenient).unwrap();
+        assert_eq!(vec![1, -2, 16], parsed.changes);
+        let skipped: Vec<(usize, usize, &str)> = parsed
+            .skipped
+            .iter()
+            .map(|error| (error.line, error.column, &*error.text))
+            .collect();
+        assert_eq!(vec![(1, 5, "oops"), (3, 1, "zz")], skipped);
+    }
+}
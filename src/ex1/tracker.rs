@@ -0,0 +1,224 @@
+use error::{parse_field, Error, Result};
+use std::collections::HashSet;
+
+/// The first frequency a tracker saw twice.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Repeat {
+    pub frequency: i64,
+    /// How many changes were applied when it was seen again.
+    pub step: u64,
+}
+
+/// Follows frequency changes as they come, remembering every frequency seen
+/// so it can tell when one is reached again.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrequencyTracker {
+    frequency: i64,
+    steps: u64,
+    seen: HashSet<i64>,
+    first_repeat: Option<Repeat>,
+}
+
+impl FrequencyTracker {
+    pub fn new() -> FrequencyTracker {
+        return FrequencyTracker::starting_at(0);
+    }
+
+    pub fn starting_at(frequency: i64) -> FrequencyTracker {
+        let mut seen = HashSet::new();
+        seen.insert(frequency);
+        return FrequencyTracker {
+            frequency,
+            steps: 0,
+            seen,
+            first_repeat: None,
+        };
+    }
+
+    pub fn frequency(&self) -> i64 {
+        return self.frequency;
+    }
+
+    /// How many changes were applied so far.
+    pub fn steps(&self) -> u64 {
+        return self.steps;
+    }
+
+    pub fn first_repeat(&self) -> Option<Repeat> {
+        return self.first_repeat;
+    }
+
+    /// Applies a change and returns the new frequency. On overflow, the change
+    /// is left out and `Error::Overflow` gives its index among all changes.
+    pub fn apply<C: Into<i64>>(&mut self, change: C) -> Result<i64> {
+        let frequency = self
+            .frequency
+            .checked_add(change.into())
+            .ok_or(Error::Overflow {
+                index: self.steps as usize,
+            })?;
+        self.frequency = frequency;
+        self.steps += 1;
+        if !self.seen.insert(frequency) && self.first_repeat.is_none() {
+            self.first_repeat = Some(Repeat {
+                frequency,
+                step: self.steps,
+            });
+        }
+        return Ok(frequency);
+    }
+
+    /// Applies a chunk of changes in order, stopping at the first overflow.
+    pub fn apply_all<C: Copy + Into<i64>>(&mut self, changes: &[C]) -> Result<i64> {
+        for &change in changes {
+            self.apply(change)?;
+        }
+        return Ok(self.frequency);
+    }
+
+    /// Saves the tracker's state as text, to be given back to `restore`.
+    ///
+    /// The first line holds the frequency, the number of steps and the first
+    /// repeat's frequency and step, or `-` if there was none yet. The second
+    /// one lists every frequency seen.
+    pub fn snapshot(&self) -> String {
+        let repeat = match self.first_repeat {
+            Some(repeat) => format!("{} {}", repeat.frequency, repeat.step),
+            None => String::from("-"),
+        };
+        let mut seen: Vec<i64> = self.seen.iter().cloned().collect();
+        seen.sort_unstable();
+        let seen: Vec<String> = seen.iter().map(i64::to_string).collect();
+        return format!(
+            "{} {} {}\n{}\n",
+            self.frequency,
+            self.steps,
+            repeat,
+            seen.join(" ")
+        );
+    }
+
+    /// Rebuilds a tracker from a `snapshot`.
+    pub fn restore(snapshot: &str) -> Result<FrequencyTracker> {
+        let mut lines = snapshot.lines();
+        let state = lines.next().unwrap_or("");
+        let mut fields = state.split_whitespace();
+        let mut next_field =
+            |expected| fields.next().ok_or_else(|| Error::missing(state, expected));
+
+        let frequency_field = next_field("a frequency")?;
+        let frequency = parse_field(state, frequency_field, "a frequency")?;
+        let steps_field = next_field("a number of steps")?;
+        let steps = parse_field(state, steps_field, "a number of steps")?;
+        let repeat_field = next_field("a repeated frequency or -")?;
+        let first_repeat = if repeat_field == "-" {
+            None
+        } else {
+            let repeat_frequency = parse_field(state, repeat_field, "a repeated frequency or -")?;
+            let step_field = next_field("the step of the repeat")?;
+            Some(Repeat {
+                frequency: repeat_frequency,
+                step: parse_field(state, step_field, "the step of the repeat")?,
+            })
+        };
+
+        let seen_line = lines.next().unwrap_or("");
+        let mut seen = HashSet::new();
+        for field in seen_line.split_whitespace() {
+            let seen_frequency =
+                parse_field(seen_line, field, "a frequency").map_err(|error| error.on_line(2))?;
+            seen.insert(seen_frequency);
+        }
+        if !seen.contains(&frequency) {
+            return Err(Error::Invalid(format!(
+                "the current frequency {} was never seen",
+                frequency
+            )));
+        }
+
+        return Ok(FrequencyTracker {
+            frequency,
+            steps,
+            seen,
+            first_repeat,
+        });
+    }
+}
+
+impl Default for FrequencyTracker {
+    fn default() -> FrequencyTracker {
+        return FrequencyTracker::new();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn track_changes() {
+        let mut tracker = FrequencyTracker::new();
+        assert_eq!(3, tracker.apply(3).unwrap());
+        assert_eq!(10, tracker.apply_all(&[3, 4]).unwrap());
+        assert_eq!(None, tracker.first_repeat());
+        tracker.apply_all(&[-2, -4, 3, 3]).unwrap();
+        assert_eq!(
+            Some(Repeat {
+                frequency: 10,
+                step: 7
+            }),
+            tracker.first_repeat()
+        );
+        tracker.apply_all(&[4, -2, -4]).unwrap();
+        assert_eq!((8, 10), (tracker.frequency(), tracker.steps()));
+        assert_eq!(10, tracker.first_repeat().unwrap().frequency);
+    }
+
+    #[test]
+    fn track_overflow() {
+        let mut tracker = FrequencyTracker::starting_at(i64::MAX - 1);
+        match tracker.apply_all(&[1i64, 1, -1]) {
+            Err(Error::Overflow { index: 1 }) => {}
+            result => panic!("unexpected result {:?}", result),
+        }
+        assert_eq!((i64::MAX, 1), (tracker.frequency(), tracker.steps()));
+    }
+
+    #[test]
+    fn snapshot_and_restore() {
+        let mut tracker = FrequencyTracker::new();
+        tracker.apply_all(&[1, -2, 3]).unwrap();
+        let snapshot = tracker.snapshot();
+        assert_eq!("2 3 -\n-1 0 1 2\n", snapshot);
+        let mut restored = FrequencyTracker::restore(&snapshot).unwrap();
+        assert_eq!(tracker, restored);
+
+        tracker.apply_all(&[1, -2, 3]).unwrap();
+        restored.apply_all(&[1, -2, 3]).unwrap();
+        assert_eq!(tracker, restored);
+        assert_eq!("4 6 1 5\n-1 0 1 2 3 4\n", restored.snapshot());
+        assert_eq!(
+            tracker,
+            FrequencyTracker::restore(&tracker.snapshot()).unwrap()
+        );
+    }
+
+    #[test]
+    fn restore_invalid_snapshot() {
+        match FrequencyTracker::restore("2 3 5\n0 2\n") {
+            Err(Error::Parse(error)) => {
+                assert_eq!((1, 6), (error.line, error.column));
+                assert_eq!("the step of the repeat", error.expected);
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
+        match FrequencyTracker::restore("2 3 -\n0 x\n") {
+            Err(Error::Parse(error)) => assert_eq!((2, 3), (error.line, error.column)),
+            result => panic!("unexpected result {:?}", result),
+        }
+        match FrequencyTracker::restore("2 3 -\n0 1\n") {
+            Err(Error::Invalid(_)) => {}
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+}
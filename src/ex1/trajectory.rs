@@ -0,0 +1,357 @@
+use super::{first_repeat, repeat_at, running_frequencies, FirstRepeat};
+use error::{Error, Result};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::convert::TryFrom;
+use std::io::Write;
+
+/// The lowest or highest frequency of the trajectory, and when it's first reached.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Extreme {
+    pub frequency: i64,
+    /// How many changes were applied to reach it, 0 being the starting frequency.
+    pub step: u128,
+}
+
+/// Everything calibration needs to know about the frequencies reached from 0.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrajectoryReport {
+    /// The frequency after one pass through the changes, which is also the drift
+    /// of every following pass.
+    pub final_frequency: i64,
+    /// Extremes of the frequencies reached until the first repeat, the starting
+    /// frequency included. When nothing ever repeats, they cover the first pass.
+    pub min: Extreme,
+    pub max: Extreme,
+    /// How many distinct frequencies are reached before the first repeat, or
+    /// `None` if no frequency is ever reached twice.
+    pub distinct_before_repeat: Option<u128>,
+    /// The first frequencies to be reached a second time, in order.
+    pub repeats: Vec<FirstRepeat>,
+}
+
+/// Analyses the frequencies reached by cycling through the changes from 0,
+/// listing up to `repeats` repeated frequencies.
+///
+/// Each change moves by the drift from one pass to the next, so the extremes
+/// it reaches are either on the first pass or on the last one before the
+/// first repeat.
+pub fn analyze_trajectory(changes: &[i32], repeats: usize) -> Result<TrajectoryReport> {
+    let frequencies: Vec<i64> =
+        running_frequencies(0, changes.iter().map(|&change| Ok(change))).collect::<Result<_>>()?;
+    let final_frequency = frequencies.last().cloned().unwrap_or(0);
+    let distinct_before_repeat = first_repeat(changes).map(|repeat| repeat.step);
+    let last_step = distinct_before_repeat.unwrap_or(changes.len() as u128);
+
+    let mut min = Extreme {
+        frequency: 0,
+        step: 0,
+    };
+    let mut max = min;
+    let length = changes.len() as u128;
+    for (index, &frequency) in frequencies.iter().enumerate() {
+        let first_step = index as u128 + 1;
+        if first_step > last_step {
+            break;
+        }
+        let last_cycle = (last_step - first_step) / length;
+        for &cycle in &[0, last_cycle] {
+            let reached = i128::from(frequency) + cycle as i128 * i128::from(final_frequency);
+            let extreme = Extreme {
+                frequency: i64::try_from(reached).map_err(|_| Error::Overflow { index })?,
+                step: cycle * length + first_step,
+            };
+            if (extreme.frequency, extreme.step) < (min.frequency, min.step) {
+                min = extreme;
+            }
+            if (extreme.frequency, Reverse(extreme.step)) > (max.frequency, Reverse(max.step)) {
+                max = extreme;
+            }
+        }
+    }
+
+    return Ok(TrajectoryReport {
+        final_frequency,
+        min,
+        max,
+        distinct_before_repeat,
+        repeats: repeated_frequencies(changes).take(repeats).collect(),
+    });
+}
+
+/// The changes of a single position that reach an already seen frequency:
+/// every cycle from `cycle` until `end`, if any.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Visits {
+    step: u128,
+    position: usize,
+    cycle: u64,
+    end: Option<u64>,
+}
+
+/// Frequencies reached a second time, in order, see `repeated_frequencies`.
+pub struct RepeatedFrequencies {
+    sums: Vec<i64>,
+    drift: i64,
+    visits: BinaryHeap<Reverse<Visits>>,
+}
+
+/// Lazily lists every frequency reached a second time while cycling through
+/// the changes from 0, in the order this happens. Never ends if the changes
+/// drift and any frequency repeats.
+///
+/// Like `first_repeat`, this groups prefix sums by residue modulo the drift.
+/// A frequency is first reached from the closest prefix sum behind it in its
+/// group, then from the next closest: each prefix sum therefore reaches a
+/// frequency for the second time on the cycles spanning the gap between the
+/// two prefix sums ahead of it.
+pub fn repeated_frequencies(changes: &[i32]) -> RepeatedFrequencies {
+    let length = changes.len();
+    let mut sums: Vec<i64> = Vec::with_capacity(length);
+    let mut drift: i64 = 0;
+    for &change in changes {
+        sums.push(drift);
+        drift += i64::from(change);
+    }
+
+    let visit = |position: usize, cycle: u64, end: Option<u64>| Visits {
+        step: u128::from(cycle) * length as u128 + position as u128,
+        position,
+        cycle,
+        end,
+    };
+    let mut visits = BinaryHeap::new();
+    if drift == 0 {
+        // Every pass is the same: each frequency comes back either later in
+        // the first pass or at the same point of the second one.
+        let mut positions: HashMap<i64, Vec<usize>> = HashMap::new();
+        for (position, &sum) in sums.iter().enumerate() {
+            positions.entry(sum).or_default().push(position);
+        }
+        for positions in positions.values() {
+            let second = match positions.get(1) {
+                Some(&position) => visit(position, 0, Some(1)),
+                None => visit(positions[0], 1, Some(2)),
+            };
+            visits.push(Reverse(second));
+        }
+    } else {
+        let modulus = drift.abs();
+        let direction = drift.signum();
+        // Within a residue class, frequencies are first reached from the
+        // furthest prefix sum along the drift, ties going to the earliest one.
+        let mut classes: Vec<(i64, Reverse<i64>, usize)> = sums
+            .iter()
+            .enumerate()
+            .map(|(position, &sum)| (sum.rem_euclid(modulus), Reverse(sum * direction), position))
+            .collect();
+        classes.sort_unstable();
+
+        for (index, &(residue, Reverse(progress), position)) in classes.iter().enumerate() {
+            let passes_to = |ahead: usize| {
+                index
+                    .checked_sub(ahead)
+                    .map(|other| classes[other])
+                    .filter(|other| other.0 == residue)
+                    .map(|(_, Reverse(other_progress), _)| {
+                        ((other_progress - progress) / modulus) as u64
+                    })
+            };
+            if let Some(start) = passes_to(1) {
+                let end = passes_to(2);
+                if end.is_none_or(|end| start < end) {
+                    visits.push(Reverse(visit(position, start, end)));
+                }
+            }
+        }
+    }
+
+    return RepeatedFrequencies {
+        sums,
+        drift,
+        visits,
+    };
+}
+
+impl Iterator for RepeatedFrequencies {
+    type Item = FirstRepeat;
+
+    fn next(&mut self) -> Option<FirstRepeat> {
+        let Reverse(visits) = self.visits.pop()?;
+        let length = self.sums.len();
+        let cycle = visits.cycle + 1;
+        if visits.end.is_none_or(|end| cycle < end) {
+            self.visits.push(Reverse(Visits {
+                step: visits.step + length as u128,
+                cycle,
+                ..visits
+            }));
+        }
+        let frequency = self.sums[visits.position] + visits.cycle as i64 * self.drift;
+        return Some(repeat_at(frequency, visits.step, length));
+    }
+}
+
+/// Writes the frequencies reached over `passes` passes through the changes as
+/// CSV, one row per step, starting with the frequency 0 at step 0.
+pub fn write_trajectory_csv<W: Write>(
+    writer: &mut W,
+    changes: &[i32],
+    passes: usize,
+) -> Result<()> {
+    writeln!(writer, "step,cycle,position,change,frequency")?;
+    writeln!(writer, "0,,,,0")?;
+    let cycled = changes.iter().cycle().take(changes.len() * passes);
+    let frequencies = running_frequencies(0, cycled.clone().map(|&change| Ok(change)));
+    for (index, (change, frequency)) in cycled.zip(frequencies).enumerate() {
+        let frequency: i64 = frequency?;
+        writeln!(
+            writer,
+            "{},{},{},{:+},{}",
+            index + 1,
+            index / changes.len(),
+            index % changes.len(),
+            change,
+            frequency
+        )?;
+    }
+    return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    /// Cycles through the changes for `max_steps`, listing the frequencies
+    /// reached a second time as (step, frequency).
+    fn simulate_repeats(changes: &[i32], max_steps: usize) -> Vec<(u128, i64)> {
+        let mut visits: HashMap<i64, usize> = HashMap::new();
+        let mut repeats = Vec::new();
+        let mut frequency: i64 = 0;
+        visits.insert(frequency, 1);
+        for (step, &change) in changes.iter().cycle().take(max_steps).enumerate() {
+            frequency += i64::from(change);
+            let count = visits.entry(frequency).or_insert(0);
+            *count += 1;
+            if *count == 2 {
+                repeats.push((step as u128 + 1, frequency));
+            }
+        }
+        return repeats;
+    }
+
+    #[test]
+    fn example_trajectory() {
+        let report = analyze_trajectory(&[3, 3, 4, -2, -4], 3).unwrap();
+        assert_eq!(4, report.final_frequency);
+        assert_eq!(
+            Extreme {
+                frequency: 0,
+                step: 0
+            },
+            report.min
+        );
+        assert_eq!(
+            Extreme {
+                frequency: 10,
+                step: 3
+            },
+            report.max
+        );
+        assert_eq!(Some(7), report.distinct_before_repeat);
+        let repeats: Vec<(i64, u64, usize)> = report
+            .repeats
+            .iter()
+            .map(|repeat| (repeat.frequency, repeat.cycle, repeat.position))
+            .collect();
+        assert_eq!(vec![(10, 1, 1), (8, 1, 4), (14, 2, 1)], repeats);
+        assert_eq!(
+            first_repeat(&[3, 3, 4, -2, -4]),
+            report.repeats.first().cloned()
+        );
+    }
+
+    #[test]
+    fn extremes_until_first_repeat() {
+        // 0, 3, 1, 4, 2, 5, then 3 again.
+        let report = analyze_trajectory(&[3, -2], 0).unwrap();
+        assert_eq!(Some(6), report.distinct_before_repeat);
+        assert_eq!(
+            Extreme {
+                frequency: 5,
+                step: 5
+            },
+            report.max
+        );
+        assert_eq!(
+            Extreme {
+                frequency: 0,
+                step: 0
+            },
+            report.min
+        );
+        let report = analyze_trajectory(&[-3, 2], 0).unwrap();
+        assert_eq!((-5, 5), (report.min.frequency, report.min.step));
+    }
+
+    #[test]
+    fn trajectory_without_repeats() {
+        let report = analyze_trajectory(&[-1, -1], 5).unwrap();
+        assert_eq!((-2, 2), (report.min.frequency, report.min.step));
+        assert_eq!((0, 0), (report.max.frequency, report.max.step));
+        assert_eq!(None, report.distinct_before_repeat);
+        assert!(report.repeats.is_empty());
+        assert_eq!(0, repeated_frequencies(&[]).count());
+        assert_eq!(2, repeated_frequencies(&[1, -1, 0]).count());
+    }
+
+    #[test]
+    fn trajectory_csv() {
+        let mut csv = Vec::new();
+        write_trajectory_csv(&mut csv, &[1, -2], 2).unwrap();
+        assert_eq!(
+            "step,cycle,position,change,frequency\n\
+             0,,,,0\n\
+             1,0,0,+1,1\n\
+             2,0,1,-2,-1\n\
+             3,1,0,+1,0\n\
+             4,1,1,-2,-2\n",
+            String::from_utf8(csv).unwrap()
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn extremes_match_simulation(changes in prop::collection::vec(-10i32..10, 1..12)) {
+            let report = analyze_trajectory(&changes, 0).unwrap();
+            let steps = report.distinct_before_repeat.unwrap_or(changes.len() as u128);
+            prop_assume!(steps <= 20_000);
+            let mut frequency: i64 = 0;
+            let (mut min, mut max) = ((0, 0), (0, 0));
+            for (step, &change) in changes.iter().cycle().take(steps as usize).enumerate() {
+                frequency += i64::from(change);
+                let step = step as u128 + 1;
+                if frequency < min.0 {
+                    min = (frequency, step);
+                }
+                if frequency > max.0 {
+                    max = (frequency, step);
+                }
+            }
+            prop_assert_eq!(min, (report.min.frequency, report.min.step));
+            prop_assert_eq!(max, (report.max.frequency, report.max.step));
+        }
+
+        #[test]
+        fn repeats_match_simulation(changes in prop::collection::vec(-10i32..10, 1..12)) {
+            let max_steps = 2_000;
+            let expected = simulate_repeats(&changes, max_steps);
+            let repeats: Vec<(u128, i64)> = repeated_frequencies(&changes)
+                .take_while(|repeat| repeat.step <= max_steps as u128)
+                .map(|repeat| (repeat.step, repeat.frequency))
+                .collect();
+            prop_assert_eq!(repeats, expected);
+        }
+    }
+}
@@ -0,0 +1,305 @@
+use super::parse_change;
+use error::{Error, Result};
+use std::collections::HashSet;
+
+const DIMENSIONS: &str = "as many components as the first change";
+
+/// Parses one vector change per line, its components such as `+3,-1,+0`
+/// separated by commas. Every line must have as many components as the
+/// first one, and `#` starts a comment running to the end of the line.
+pub fn parse_vector_changes(content: &str) -> Result<Vec<Vec<i64>>> {
+    let mut changes: Vec<Vec<i64>> = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let code = &line[..line.find('#').unwrap_or(line.len())];
+        if code.trim().is_empty() {
+            continue;
+        }
+        let mut change = Vec::new();
+        for component in code.split(',') {
+            let component = component.trim();
+            let value = if component.is_empty() {
+                Err(Error::parse(line, component, "a frequency change"))
+            } else if changes
+                .first()
+                .is_some_and(|first| change.len() == first.len())
+            {
+                Err(Error::parse(line, component, DIMENSIONS))
+            } else {
+                parse_change(line, component)
+            };
+            change.push(value.map_err(|error| error.on_line(index + 1))?);
+        }
+        if changes
+            .first()
+            .is_some_and(|first| change.len() != first.len())
+        {
+            return Err(Error::missing(code.trim_end(), DIMENSIONS).on_line(index + 1));
+        }
+        changes.push(change);
+    }
+    return Ok(changes);
+}
+
+/// Fails with `Error::Invalid` unless every change has `dimensions` components.
+fn check_dimensions(dimensions: usize, changes: &[Vec<i64>]) -> Result<()> {
+    match changes.iter().position(|change| change.len() != dimensions) {
+        Some(index) => Err(Error::Invalid(format!(
+            "change {} has {} components instead of {}",
+            index + 1,
+            changes[index].len(),
+            dimensions
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Adds the changes component by component, failing with `Error::Overflow`
+/// at the first change whose result doesn't fit.
+pub fn sum_vectors(dimensions: usize, changes: &[Vec<i64>]) -> Result<Vec<i64>> {
+    check_dimensions(dimensions, changes)?;
+    let mut state = vec![0i64; dimensions];
+    for (index, change) in changes.iter().enumerate() {
+        for (component, &value) in state.iter_mut().zip(change) {
+            *component = component
+                .checked_add(value)
+                .ok_or(Error::Overflow { index })?;
+        }
+    }
+    return Ok(state);
+}
+
+/// A vector state reached for the second time, and when.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VectorRepeat {
+    pub state: Vec<i64>,
+    /// How many full passes through the changes were made before, starting at 0.
+    pub cycle: u64,
+    /// Index of the change that reaches the state again.
+    pub position: usize,
+    /// How many changes were applied in total, the repeating one included.
+    pub step: u128,
+}
+
+/// Finds the first vector state reached twice while cycling through the
+/// changes from the origin, or `None` when it provably never happens. Like
+/// `sum_vectors`, every change must have `dimensions` components.
+///
+/// This works like `first_repeat`, along a component where the drift isn't 0:
+/// states only meet if they're a whole number of drifts apart, so they're
+/// grouped by what's left once brought back by as many drifts as that
+/// component allows, and ordered by how many drifts were taken away.
+pub fn first_vector_repeat(
+    dimensions: usize,
+    changes: &[Vec<i64>],
+) -> Result<Option<VectorRepeat>> {
+    check_dimensions(dimensions, changes)?;
+    let mut sums: Vec<Vec<i64>> = Vec::with_capacity(changes.len());
+    let mut state = vec![0i64; dimensions];
+    for (index, change) in changes.iter().enumerate() {
+        sums.push(state.clone());
+        for (component, &value) in state.iter_mut().zip(change) {
+            *component = component
+                .checked_add(value)
+                .ok_or(Error::Overflow { index })?;
+        }
+    }
+    let drift = state;
+
+    let repeat = match drift.iter().position(|&component| component != 0) {
+        None => first_vector_repeat_without_drift(&sums),
+        Some(axis) => first_vector_repeat_with_drift(&sums, &drift, axis),
+    };
+    let length = sums.len() as u128;
+    return Ok(repeat.map(|(step, state)| VectorRepeat {
+        state,
+        cycle: ((step - 1) / length) as u64,
+        position: ((step - 1) % length) as usize,
+        step,
+    }));
+}
+
+fn first_vector_repeat_without_drift(sums: &[Vec<i64>]) -> Option<(u128, Vec<i64>)> {
+    let mut seen: HashSet<&[i64]> = HashSet::new();
+    for (index, sum) in sums.iter().enumerate() {
+        if !seen.insert(sum) {
+            return Some((index as u128, sum.clone()));
+        }
+    }
+    return sums
+        .first()
+        .map(|first| (sums.len() as u128, first.clone()));
+}
+
+fn first_vector_repeat_with_drift(
+    sums: &[Vec<i64>],
+    drift: &[i64],
+    axis: usize,
+) -> Option<(u128, Vec<i64>)> {
+    let modulus = i128::from(drift[axis].abs());
+    let direction = i128::from(drift[axis].signum());
+    // Ordered by class, then by drifts taken away, then by position.
+    let mut classes: Vec<(Vec<i128>, i128, usize)> = sums
+        .iter()
+        .enumerate()
+        .map(|(index, sum)| {
+            let drifts = i128::from(sum[axis]).div_euclid(modulus) * direction;
+            let class = sum
+                .iter()
+                .zip(drift)
+                .map(|(&component, &drift)| i128::from(component) - drifts * i128::from(drift))
+                .collect();
+            (class, drifts, index)
+        })
+        .collect();
+    classes.sort_unstable();
+
+    let length = sums.len() as u128;
+    let first = classes
+        .windows(2)
+        .filter(|pair| pair[0].0 == pair[1].0)
+        .map(|pair| {
+            let (_, drifts, index) = pair[0];
+            let (_, next_drifts, next_index) = pair[1];
+            if drifts == next_drifts {
+                // Both are reached in the first pass.
+                (next_index as u128, sums[index].clone())
+            } else {
+                let passes = (next_drifts - drifts) as u128;
+                (passes * length + index as u128, sums[next_index].clone())
+            }
+        })
+        .min_by_key(|&(step, _)| step);
+    return first;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::first_repeat;
+    use super::*;
+    use proptest::prelude::*;
+    use std::collections::HashSet;
+
+    fn simulate_vector_repeat(changes: &[Vec<i64>], max_steps: u128) -> Option<(u128, Vec<i64>)> {
+        let mut state = vec![0; changes.first().map_or(0, Vec::len)];
+        let mut seen: HashSet<Vec<i64>> = HashSet::new();
+        seen.insert(state.clone());
+        let mut step = 0;
+        while step < max_steps && !changes.is_empty() {
+            let change = &changes[(step % changes.len() as u128) as usize];
+            for (component, value) in state.iter_mut().zip(change) {
+                *component += value;
+            }
+            step += 1;
+            if !seen.insert(state.clone()) {
+                return Some((step, state));
+            }
+        }
+        return None;
+    }
+
+    #[test]
+    fn parse_vectors() {
+        let changes = parse_vector_changes("+3,-1,+0\n# axis check\n-0x2, +1 ,0 # z\n").unwrap();
+        assert_eq!(vec![vec![3, -1, 0], vec![-2, 1, 0]], changes);
+        assert_eq!(vec![1, 0, 0], sum_vectors(3, &changes).unwrap());
+
+        for &(content, line, column, text) in &[
+            ("+1,+2\n+1,+2,+3\n", 2, 7, "+3"),
+            ("+1,+2\n+1 # +2\n", 2, 3, ""),
+            ("+1,+2\n+1\n", 2, 3, ""),
+            ("+1,+2\n+1,x\n", 2, 4, "x"),
+            ("+1,,+2\n", 1, 4, ""),
+        ] {
+            match parse_vector_changes(content) {
+                Err(Error::Parse(error)) => assert_eq!(
+                    (line, column, text),
+                    (error.line, error.column, &*error.text),
+                    "{}",
+                    content
+                ),
+                result => panic!("unexpected result {:?} for {}", result, content),
+            }
+        }
+    }
+
+    #[test]
+    fn vector_repeats() {
+        let changes = vec![vec![1, 0], vec![0, 1], vec![-1, 0], vec![0, -1]];
+        assert_eq!(
+            Some(VectorRepeat {
+                state: vec![0, 0],
+                cycle: 0,
+                position: 3,
+                step: 4
+            }),
+            first_vector_repeat(2, &changes).unwrap()
+        );
+
+        // Drifts by (1, 1) a pass, coming back to (2, 2) after two passes.
+        let changes = vec![vec![2, 2], vec![-1, -1]];
+        assert_eq!(
+            Some(VectorRepeat {
+                state: vec![2, 2],
+                cycle: 1,
+                position: 1,
+                step: 4
+            }),
+            first_vector_repeat(2, &changes).unwrap()
+        );
+
+        // The first axis repeats on its own, but never along with the second.
+        let changes = vec![vec![1, 0], vec![-1, 1]];
+        assert_eq!(None, first_vector_repeat(2, &changes).unwrap());
+        assert_eq!(None, first_vector_repeat(2, &[]).unwrap());
+    }
+
+    #[test]
+    fn vector_dimensions_must_match() {
+        for changes in &[vec![vec![1, 2], vec![3]], vec![vec![1, 2], vec![3, 4, 5]]] {
+            match sum_vectors(2, changes) {
+                Err(Error::Invalid(message)) => {
+                    assert_eq!(
+                        format!("change 2 has {} components instead of 2", changes[1].len()),
+                        message
+                    )
+                }
+                result => panic!("unexpected result {:?}", result),
+            }
+            assert!(first_vector_repeat(2, changes).is_err());
+        }
+    }
+
+    #[test]
+    fn vector_overflow() {
+        match first_vector_repeat(2, &[vec![0, i64::MAX], vec![0, 1]]) {
+            Err(Error::Overflow { index: 1 }) => {}
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn single_axis_matches_scalar(changes in prop::collection::vec(-20i32..20, 0..30)) {
+            let vectors: Vec<Vec<i64>> = changes.iter().map(|&change| vec![i64::from(change)]).collect();
+            let repeat = first_vector_repeat(1, &vectors).unwrap();
+            prop_assert_eq!(
+                repeat.map(|repeat| (repeat.step, repeat.state[0])),
+                first_repeat(&changes).map(|repeat| (repeat.step, repeat.frequency))
+            );
+        }
+
+        #[test]
+        fn vector_repeat_matches_simulation(
+            changes in prop::collection::vec(prop::collection::vec(-4i64..4, 3), 1..10)
+        ) {
+            let max_steps = 2_000;
+            match first_vector_repeat(3, &changes).unwrap() {
+                Some(repeat) if repeat.step <= max_steps => prop_assert_eq!(
+                    simulate_vector_repeat(&changes, max_steps),
+                    Some((repeat.step, repeat.state))
+                ),
+                _ => prop_assert_eq!(simulate_vector_repeat(&changes, max_steps), None),
+            }
+        }
+    }
+}
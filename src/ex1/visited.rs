@@ -0,0 +1,339 @@
+use std::collections::{BTreeMap, HashSet};
+use std::mem;
+
+/// Above this many bits, a dense bitset is considered too big (16 MiB).
+const DENSE_LIMIT: u64 = 1 << 27;
+/// Values in a compressed bitmap chunk: the low 16 bits of a frequency.
+const CHUNK_BITS: u32 = 16;
+/// Past this many values, a chunk is cheaper stored as a bitmap than as an array.
+const ARRAY_LIMIT: usize = 4096;
+
+/// Remembers which frequencies were visited by the repeat search.
+pub trait VisitedSet {
+    /// Marks `frequency` as visited, returning whether it already was.
+    fn visit(&mut self, frequency: i64) -> bool;
+    /// Approximate heap memory used, in bytes.
+    fn memory(&self) -> usize;
+}
+
+impl VisitedSet for HashSet<i64> {
+    fn visit(&mut self, frequency: i64) -> bool {
+        return !self.insert(frequency);
+    }
+
+    fn memory(&self) -> usize {
+        // One control byte per bucket besides the value itself.
+        return self.capacity() * (mem::size_of::<i64>() + 1);
+    }
+}
+
+/// One bit per frequency of a bounded range.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DenseBitSet {
+    lower: i64,
+    words: Vec<u64>,
+}
+
+impl DenseBitSet {
+    /// A bitset for frequencies from `lower` to `upper`, both included.
+    pub fn new(lower: i64, upper: i64) -> DenseBitSet {
+        let bits = (i128::from(upper) - i128::from(lower) + 1).max(0) as usize;
+        return DenseBitSet {
+            lower,
+            words: vec![0; bits.div_ceil(64)],
+        };
+    }
+}
+
+impl VisitedSet for DenseBitSet {
+    /// Panics if `frequency` is outside the range the bitset was made for.
+    fn visit(&mut self, frequency: i64) -> bool {
+        let bit = (i128::from(frequency) - i128::from(self.lower)) as usize;
+        let word = &mut self.words[bit / 64];
+        let mask = 1 << (bit % 64);
+        let visited = *word & mask != 0;
+        *word |= mask;
+        return visited;
+    }
+
+    fn memory(&self) -> usize {
+        return self.words.capacity() * mem::size_of::<u64>();
+    }
+}
+
+/// The low bits of the frequencies sharing the same high bits.
+#[derive(Debug, Clone, PartialEq)]
+enum Chunk {
+    /// Sorted, while there are few of them.
+    Array(Vec<u16>),
+    Bitmap(Box<[u64]>),
+}
+
+/// A roaring-style bitmap: frequencies are grouped by their high bits, each
+/// group storing its low bits as a sorted array or, once full enough, a bitmap.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CompressedBitmap {
+    chunks: BTreeMap<i64, Chunk>,
+}
+
+impl CompressedBitmap {
+    pub fn new() -> CompressedBitmap {
+        return CompressedBitmap::default();
+    }
+}
+
+impl VisitedSet for CompressedBitmap {
+    fn visit(&mut self, frequency: i64) -> bool {
+        let key = frequency >> CHUNK_BITS;
+        let low = (frequency & 0xffff) as u16;
+        let chunk = self
+            .chunks
+            .entry(key)
+            .or_insert_with(|| Chunk::Array(Vec::new()));
+        match *chunk {
+            Chunk::Array(ref mut values) => match values.binary_search(&low) {
+                Ok(_) => return true,
+                Err(index) if values.len() < ARRAY_LIMIT => {
+                    values.insert(index, low);
+                    return false;
+                }
+                Err(_) => {}
+            },
+            Chunk::Bitmap(ref mut words) => {
+                let mask = 1 << (low % 64);
+                let visited = words[usize::from(low / 64)] & mask != 0;
+                words[usize::from(low / 64)] |= mask;
+                return visited;
+            }
+        }
+
+        // The array is full: switch it to a bitmap.
+        let mut words = vec![0u64; 1 << (CHUNK_BITS - 6)].into_boxed_slice();
+        if let Chunk::Array(ref values) = *chunk {
+            for &value in values.iter().chain(Some(&low)) {
+                words[usize::from(value / 64)] |= 1 << (value % 64);
+            }
+        }
+        *chunk = Chunk::Bitmap(words);
+        return false;
+    }
+
+    fn memory(&self) -> usize {
+        let entry = mem::size_of::<i64>() + mem::size_of::<Chunk>();
+        return self
+            .chunks
+            .values()
+            .map(|chunk| match *chunk {
+                Chunk::Array(ref values) => entry + values.capacity() * mem::size_of::<u16>(),
+                Chunk::Bitmap(ref words) => entry + words.len() * mem::size_of::<u64>(),
+            })
+            .sum();
+    }
+}
+
+/// Which visited set suits a search.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Backend {
+    /// The frequencies stay within this range, small enough for a bitset.
+    Dense { lower: i64, upper: i64 },
+    /// The range is too wide for a bitset, but dense enough for chunks to hold
+    /// more than a few frequencies.
+    Compressed,
+    /// The frequencies are too sparse, or their range couldn't be bounded.
+    Hash,
+}
+
+/// Picks a visited set for cycling through `changes` from `base_frequency`
+/// until the first repeat, from the range of the prefix sums.
+///
+/// Two frequencies reached from prefix sums `w` apart in the first pass can
+/// only meet after at most `w / drift` passes, so every frequency visited
+/// before the first repeat lies within the first pass's range widened by `w`
+/// in the direction of the drift.
+pub fn choose_backend(base_frequency: i64, changes: &[i32]) -> Backend {
+    let mut frequency = i128::from(base_frequency);
+    let (mut lowest, mut highest) = (frequency, frequency);
+    for &change in changes {
+        frequency += i128::from(change);
+        lowest = lowest.min(frequency);
+        highest = highest.max(frequency);
+    }
+    let drift = frequency - i128::from(base_frequency);
+    let width = highest - lowest;
+    let (lower, upper) = match drift {
+        0 => (lowest, highest),
+        drift if drift > 0 => (lowest, highest + width + drift),
+        drift => (lowest - width + drift, highest),
+    };
+    if lower < i128::from(i64::MIN) || upper > i128::from(i64::MAX) {
+        return Backend::Hash;
+    }
+
+    let span = (upper - lower + 1) as u128;
+    if span <= u128::from(DENSE_LIMIT) {
+        return Backend::Dense {
+            lower: lower as i64,
+            upper: upper as i64,
+        };
+    }
+    let chunks = span >> CHUNK_BITS;
+    if chunks <= changes.len() as u128 {
+        return Backend::Compressed;
+    }
+    return Backend::Hash;
+}
+
+/// The visited set picked by `choose_backend`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Visited {
+    Dense(DenseBitSet),
+    Compressed(CompressedBitmap),
+    Hash(HashSet<i64>),
+}
+
+impl Visited {
+    pub fn new(backend: Backend) -> Visited {
+        match backend {
+            Backend::Dense { lower, upper } => Visited::Dense(DenseBitSet::new(lower, upper)),
+            Backend::Compressed => Visited::Compressed(CompressedBitmap::new()),
+            Backend::Hash => Visited::Hash(HashSet::new()),
+        }
+    }
+
+    /// The visited set best suited to cycling through `changes`.
+    pub fn for_changes(base_frequency: i64, changes: &[i32]) -> Visited {
+        return Visited::new(choose_backend(base_frequency, changes));
+    }
+}
+
+impl VisitedSet for Visited {
+    fn visit(&mut self, frequency: i64) -> bool {
+        match *self {
+            Visited::Dense(ref mut set) => set.visit(frequency),
+            Visited::Compressed(ref mut set) => set.visit(frequency),
+            Visited::Hash(ref mut set) => set.visit(frequency),
+        }
+    }
+
+    fn memory(&self) -> usize {
+        match *self {
+            Visited::Dense(ref set) => set.memory(),
+            Visited::Compressed(ref set) => set.memory(),
+            Visited::Hash(ref set) => set.memory(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ex1;
+    use proptest::prelude::*;
+
+    fn visit_all<V: VisitedSet>(set: &mut V, frequencies: &[i64]) -> Vec<bool> {
+        return frequencies
+            .iter()
+            .map(|&frequency| set.visit(frequency))
+            .collect();
+    }
+
+    #[test]
+    fn backends_agree() {
+        let frequencies = [0, 5, -3, 5, 0, 70_000, -70_000, 70_000, -3];
+        let expected = vec![false, false, false, true, true, false, false, true, true];
+        assert_eq!(expected, visit_all(&mut HashSet::new(), &frequencies));
+        assert_eq!(
+            expected,
+            visit_all(&mut DenseBitSet::new(-70_000, 70_000), &frequencies)
+        );
+        assert_eq!(
+            expected,
+            visit_all(&mut CompressedBitmap::new(), &frequencies)
+        );
+    }
+
+    #[test]
+    fn compressed_chunks_turn_into_bitmaps() {
+        let mut set = CompressedBitmap::new();
+        let frequencies: Vec<i64> = (0..ARRAY_LIMIT as i64 + 10)
+            .map(|value| value * 3)
+            .collect();
+        assert!(visit_all(&mut set, &frequencies)
+            .iter()
+            .all(|&visited| !visited));
+        assert!(visit_all(&mut set, &frequencies)
+            .iter()
+            .all(|&visited| visited));
+        assert!(!set.visit(1));
+        // Every value fits in a single chunk, now a 8 KiB bitmap.
+        assert!(set.memory() < 9 * 1024);
+    }
+
+    #[test]
+    fn choose_backends() {
+        assert_eq!(
+            Backend::Dense {
+                lower: 0,
+                upper: 24
+            },
+            choose_backend(0, &[3, 3, 4, -2, -4])
+        );
+        assert_eq!(
+            Backend::Dense {
+                lower: -24,
+                upper: 0
+            },
+            choose_backend(0, &[-3, -3, -4, 2, 4])
+        );
+        assert_eq!(
+            Backend::Dense {
+                lower: 100,
+                upper: 101
+            },
+            choose_backend(100, &[1, -1])
+        );
+        let wide: Vec<i32> = (0..4000)
+            .map(|index| {
+                if index % 2 == 0 {
+                    100_000_000
+                } else {
+                    -99_999_999
+                }
+            })
+            .collect();
+        assert_eq!(Backend::Compressed, choose_backend(0, &wide));
+        assert_eq!(Backend::Hash, choose_backend(0, &[i32::MAX, i32::MAX, -1]));
+        assert_eq!(Backend::Hash, choose_backend(i64::MAX - 1, &[1, 1]));
+    }
+
+    #[test]
+    fn memory_on_wide_swings() {
+        // The input of the `day_1_visited` benchmark: two million frequencies
+        // are visited before the first repeat.
+        let changes = [1_000_000, -999_999];
+        let memory = |backend| {
+            let mut visited = Visited::new(backend);
+            let repeat =
+                ex1::find_repeated_frequency_with(0, &changes, None, &mut visited).unwrap();
+            assert_eq!(Some(1_000_000), repeat);
+            return visited.memory();
+        };
+        let dense = memory(choose_backend(0, &changes));
+        let compressed = memory(Backend::Compressed);
+        let hash = memory(Backend::Hash);
+        // About 250 KB for both bitsets, and 33 MB for the hash set.
+        assert!(dense < 300 * 1024);
+        assert!(compressed < 2 * dense);
+        assert!(hash > 50 * dense);
+    }
+
+    proptest! {
+        #[test]
+        fn compressed_matches_hash_set(frequencies in prop::collection::vec(-300_000i64..300_000, 0..500)) {
+            prop_assert_eq!(
+                visit_all(&mut CompressedBitmap::new(), &frequencies),
+                visit_all(&mut HashSet::new(), &frequencies)
+            );
+        }
+    }
+}
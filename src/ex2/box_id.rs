@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// What box IDs are compared and counted by.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Unit {
+    Byte,
+    Char,
+    /// What a reader sees as a single letter, such as `e` and a combining accent.
+    Grapheme,
+}
+
+/// Whether letters differing only by their case are the same.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CaseFolding {
+    None,
+    /// Only ASCII letters are lowercased.
+    Ascii,
+    /// Every letter is lowercased.
+    Unicode,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IdOptions {
+    pub unit: Unit,
+    pub folding: CaseFolding,
+}
+
+impl Default for IdOptions {
+    /// Chars, with no case folding, as the puzzle expects.
+    fn default() -> IdOptions {
+        return IdOptions {
+            unit: Unit::Char,
+            folding: CaseFolding::None,
+        };
+    }
+}
+
+/// A box ID split into the units it's compared by, once case folded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoxId {
+    id: String,
+    folded: String,
+    /// Byte offsets where units start, followed by the length of `folded`.
+    bounds: Vec<usize>,
+}
+
+impl BoxId {
+    pub fn new(id: &str, options: IdOptions) -> BoxId {
+        let folded = match options.folding {
+            CaseFolding::None => String::from(id),
+            CaseFolding::Ascii => id.to_ascii_lowercase(),
+            CaseFolding::Unicode => id.to_lowercase(),
+        };
+        let mut bounds: Vec<usize> = match options.unit {
+            Unit::Byte => (0..folded.len()).collect(),
+            Unit::Char => folded.char_indices().map(|(offset, _)| offset).collect(),
+            Unit::Grapheme => folded
+                .grapheme_indices(true)
+                .map(|(offset, _)| offset)
+                .collect(),
+        };
+        bounds.push(folded.len());
+        return BoxId {
+            id: String::from(id),
+            folded,
+            bounds,
+        };
+    }
+
+    /// The ID as given, before case folding.
+    pub fn as_str(&self) -> &str {
+        return &self.id;
+    }
+
+    /// How many units the ID has.
+    pub fn len(&self) -> usize {
+        return self.bounds.len() - 1;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.len() == 0;
+    }
+
+    /// The bytes of a unit. With `Unit::Byte`, these may not be valid UTF-8.
+    pub fn unit(&self, index: usize) -> &[u8] {
+        return &self.folded.as_bytes()[self.bounds[index]..self.bounds[index + 1]];
+    }
+
+    pub fn units(&self) -> Vec<&[u8]> {
+        return (0..self.len()).map(|index| self.unit(index)).collect();
+    }
+
+    /// How many times each unit appears.
+    pub fn unit_counts(&self) -> HashMap<&[u8], usize> {
+        let mut counts = HashMap::new();
+        for index in 0..self.len() {
+            *counts.entry(self.unit(index)).or_insert(0) += 1;
+        }
+        return counts;
+    }
+}
+
+/// Splits the input into box IDs, see `parse_ids`.
+pub fn parse_box_ids(content: &str, options: IdOptions) -> Vec<BoxId> {
+    return content
+        .split_whitespace()
+        .map(|id| BoxId::new(id, options))
+        .collect();
+}
+
+/// Joins units back into text, replacing what isn't valid UTF-8.
+pub fn join_units(units: &[&[u8]]) -> String {
+    return String::from_utf8_lossy(&units.concat()).into_owned();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(unit: Unit, folding: CaseFolding) -> IdOptions {
+        return IdOptions { unit, folding };
+    }
+
+    #[test]
+    fn split_into_units() {
+        // An `e` followed by a combining acute accent.
+        let id = "cafe\u{301}s";
+        let lengths: Vec<usize> = [Unit::Byte, Unit::Char, Unit::Grapheme]
+            .iter()
+            .map(|&unit| BoxId::new(id, options(unit, CaseFolding::None)).len())
+            .collect();
+        assert_eq!(vec![7, 6, 5], lengths);
+        let graphemes = BoxId::new(id, options(Unit::Grapheme, CaseFolding::None));
+        assert_eq!("e\u{301}".as_bytes(), graphemes.unit(3));
+        assert_eq!(id, graphemes.as_str());
+        assert!(BoxId::new("", IdOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn fold_cases() {
+        let id = "AbÉé";
+        let counts = |folding| {
+            let id = BoxId::new(id, options(Unit::Char, folding));
+            let mut counts: Vec<usize> = id.unit_counts().values().cloned().collect();
+            counts.sort_unstable();
+            return counts;
+        };
+        assert_eq!(vec![1, 1, 1, 1], counts(CaseFolding::None));
+        assert_eq!(vec![1, 1, 1, 1], counts(CaseFolding::Ascii));
+        assert_eq!(vec![1, 1, 2], counts(CaseFolding::Unicode));
+        let ascii = BoxId::new("AaB", options(Unit::Byte, CaseFolding::Ascii));
+        assert_eq!(Some(&2), ascii.unit_counts().get(&b"a"[..]));
+    }
+
+    #[test]
+    fn count_past_small_counters() {
+        let id = BoxId::new(&"a".repeat(300), IdOptions::default());
+        assert_eq!(Some(&300), id.unit_counts().get(&b"a"[..]));
+    }
+
+    #[test]
+    fn join_byte_units() {
+        let id = BoxId::new("é", options(Unit::Byte, CaseFolding::None));
+        assert_eq!("é", join_units(&id.units()));
+        assert_eq!("\u{fffd}", join_units(&id.units()[..1]));
+    }
+}
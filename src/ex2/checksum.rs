@@ -0,0 +1,175 @@
+use super::box_id::{BoxId, IdOptions};
+use error::{Error, Result};
+
+/// Combines the bucket sizes, given in the order the multiplicities were
+/// passed to `Checksum::new`.
+pub type CombineFn = Box<dyn Fn(&[usize]) -> i64>;
+
+/// How the number of IDs in each bucket makes up the checksum.
+pub enum Combine {
+    Product,
+    Sum,
+    Custom(CombineFn),
+}
+
+/// The IDs having a letter that appears exactly `multiplicity` times.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bucket {
+    pub multiplicity: usize,
+    /// Indices of the IDs, in order.
+    pub ids: Vec<usize>,
+}
+
+/// A checksum along with the buckets it was computed from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChecksumReport {
+    pub value: i64,
+    /// One bucket per multiplicity, in the order they were given.
+    pub buckets: Vec<Bucket>,
+}
+
+/// Checksums counting the IDs with a letter appearing exactly so many times,
+/// for each of the chosen multiplicities. The puzzle's checksum is the product
+/// for multiplicities 2 and 3.
+pub struct Checksum {
+    multiplicities: Vec<usize>,
+    combine: Combine,
+}
+
+impl Checksum {
+    /// Keeps the multiplicities in the given order, ignoring repeated ones.
+    pub fn new(multiplicities: &[usize], combine: Combine) -> Checksum {
+        let mut unique: Vec<usize> = Vec::with_capacity(multiplicities.len());
+        for &multiplicity in multiplicities {
+            if !unique.contains(&multiplicity) {
+                unique.push(multiplicity);
+            }
+        }
+        return Checksum {
+            multiplicities: unique,
+            combine,
+        };
+    }
+
+    /// Sorts the IDs into buckets and combines their sizes, failing with
+    /// `Error::Invalid` if a product or sum doesn't fit in an `i64`.
+    pub fn compute(&self, ids: &[String]) -> Result<ChecksumReport> {
+        let ids: Vec<BoxId> = ids
+            .iter()
+            .map(|id| BoxId::new(id, IdOptions::default()))
+            .collect();
+        return self.compute_box_ids(&ids);
+    }
+
+    /// Like `compute`, counting how many times each unit of the IDs appears.
+    pub fn compute_box_ids(&self, ids: &[BoxId]) -> Result<ChecksumReport> {
+        let mut buckets: Vec<Bucket> = self
+            .multiplicities
+            .iter()
+            .map(|&multiplicity| Bucket {
+                multiplicity,
+                ids: Vec::new(),
+            })
+            .collect();
+        for (index, id) in ids.iter().enumerate() {
+            let occurrences = id.unit_counts();
+            for bucket in &mut buckets {
+                if occurrences
+                    .values()
+                    .any(|&count| count == bucket.multiplicity)
+                {
+                    bucket.ids.push(index);
+                }
+            }
+        }
+
+        let sizes: Vec<usize> = buckets.iter().map(|bucket| bucket.ids.len()).collect();
+        let overflow = || Error::Invalid(String::from("the checksum overflows"));
+        let value = match self.combine {
+            Combine::Product => sizes.iter().try_fold(1i64, |value, &size| {
+                value.checked_mul(size as i64).ok_or_else(overflow)
+            })?,
+            Combine::Sum => sizes.iter().try_fold(0i64, |value, &size| {
+                value.checked_add(size as i64).ok_or_else(overflow)
+            })?,
+            Combine::Custom(ref combine) => combine(&sizes),
+        };
+        return Ok(ChecksumReport { value, buckets });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::box_id::{CaseFolding, Unit};
+    use super::super::calculate_checksum;
+    use super::*;
+    use proptest::prelude::*;
+
+    fn example_ids() -> Vec<String> {
+        return vec![
+            "abcdef", "bababc", "abbcde", "abcccd", "aabcdd", "abcdee", "ababab", "aaaabb",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect();
+    }
+
+    #[test]
+    fn checksum_buckets() {
+        let report = Checksum::new(&[4, 3, 2], Combine::Product)
+            .compute(&example_ids())
+            .unwrap();
+        let buckets: Vec<(usize, Vec<usize>)> = report
+            .buckets
+            .into_iter()
+            .map(|bucket| (bucket.multiplicity, bucket.ids))
+            .collect();
+        assert_eq!(
+            vec![(4, vec![7]), (3, vec![1, 3, 6]), (2, vec![1, 2, 4, 5, 7])],
+            buckets
+        );
+        assert_eq!(15, report.value);
+    }
+
+    #[test]
+    fn combine_bucket_sizes() {
+        let ids = example_ids();
+        let sum = Checksum::new(&[2, 3, 4], Combine::Sum)
+            .compute(&ids)
+            .unwrap();
+        assert_eq!(9, sum.value);
+        let weighted = Combine::Custom(Box::new(|sizes: &[usize]| {
+            sizes.iter().map(|&size| size as i64 * 10).sum::<i64>() - 1
+        }));
+        let custom = Checksum::new(&[2, 3], weighted).compute(&ids).unwrap();
+        assert_eq!(79, custom.value);
+        let first = Combine::Custom(Box::new(|sizes: &[usize]| sizes[0] as i64));
+        let ordered = Checksum::new(&[3, 2, 3], first).compute(&ids).unwrap();
+        assert_eq!((3, 2), (ordered.value, ordered.buckets.len()));
+        let none = Checksum::new(&[], Combine::Product).compute(&ids).unwrap();
+        assert_eq!((1, 0), (none.value, none.buckets.len()));
+    }
+
+    #[test]
+    fn checksum_with_folded_cases() {
+        let options = IdOptions {
+            unit: Unit::Char,
+            folding: CaseFolding::Unicode,
+        };
+        let ids = vec![BoxId::new("aAbÉé", options), BoxId::new("xXx", options)];
+        let report = Checksum::new(&[2, 3], Combine::Product)
+            .compute_box_ids(&ids)
+            .unwrap();
+        assert_eq!(vec![0], report.buckets[0].ids);
+        assert_eq!(vec![1], report.buckets[1].ids);
+        assert_eq!(1, report.value);
+    }
+
+    proptest! {
+        #[test]
+        fn pair_and_triple_product_matches_checksum(ids in prop::collection::vec("[a-d]{0,8}", 0..30)) {
+            let report = Checksum::new(&[2, 3], Combine::Product).compute(&ids).unwrap();
+            prop_assert_eq!(report.value, i64::from(calculate_checksum(ids)));
+        }
+    }
+}
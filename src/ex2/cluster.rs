@@ -0,0 +1,203 @@
+use super::box_id::{join_units, BoxId, IdOptions};
+use super::edit::{common_units, find_close_box_ids, ClosePair, Metric};
+use error::Result;
+use std::collections::BTreeMap;
+use std::io::Write;
+
+/// IDs linked by the similarity graph, directly or through other IDs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cluster {
+    /// Indices of the IDs, in order.
+    pub ids: Vec<usize>,
+    /// Letters every ID of the cluster has, in order.
+    pub common: String,
+}
+
+/// The box IDs, linked whenever they're close enough to each other.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimilarityGraph {
+    ids: Vec<BoxId>,
+    edges: Vec<ClosePair>,
+}
+
+/// Finds the root of `node`, pointing everything on the way straight at it.
+fn find_root(parents: &mut [usize], node: usize) -> usize {
+    let mut root = node;
+    while parents[root] != root {
+        root = parents[root];
+    }
+    let mut node = node;
+    while parents[node] != root {
+        let parent = parents[node];
+        parents[node] = root;
+        node = parent;
+    }
+    return root;
+}
+
+impl SimilarityGraph {
+    /// Links every pair of IDs at most `max_distance` apart, see `find_close_ids`.
+    pub fn build(ids: &[String], metric: Metric, max_distance: usize) -> SimilarityGraph {
+        let ids = ids
+            .iter()
+            .map(|id| BoxId::new(id, IdOptions::default()))
+            .collect();
+        return SimilarityGraph::from_box_ids(ids, metric, max_distance);
+    }
+
+    /// Like `build`, comparing the IDs by their units.
+    pub fn from_box_ids(ids: Vec<BoxId>, metric: Metric, max_distance: usize) -> SimilarityGraph {
+        let edges = find_close_box_ids(&ids, metric, max_distance);
+        return SimilarityGraph { ids, edges };
+    }
+
+    pub fn edges(&self) -> &[ClosePair] {
+        return &self.edges;
+    }
+
+    /// Groups the linked IDs, ordered by their first ID. IDs close to no other
+    /// one aren't part of any cluster.
+    ///
+    /// The common units are found by aligning the cluster's first ID with
+    /// every other one in turn, only keeping what's left each time: they're
+    /// shared by every ID, although not always the longest such units.
+    pub fn clusters(&self) -> Vec<Cluster> {
+        let mut parents: Vec<usize> = (0..self.ids.len()).collect();
+        for edge in &self.edges {
+            let first = find_root(&mut parents, edge.first);
+            let second = find_root(&mut parents, edge.second);
+            parents[first.max(second)] = first.min(second);
+        }
+
+        let mut members: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        for edge in &self.edges {
+            let root = find_root(&mut parents, edge.first);
+            members.entry(root).or_default().push(edge.first);
+            members.entry(root).or_default().push(edge.second);
+        }
+        return members
+            .into_values()
+            .map(|mut ids| {
+                ids.sort_unstable();
+                ids.dedup();
+                let common = ids[1..]
+                    .iter()
+                    .fold(self.ids[ids[0]].units(), |common, &id| {
+                        return common_units(&common, &self.ids[id].units());
+                    });
+                Cluster {
+                    ids,
+                    common: join_units(&common),
+                }
+            })
+            .collect();
+    }
+
+    /// Writes the graph in the DOT format, each node labelled with its ID and
+    /// each edge with the distance between its IDs.
+    pub fn write_dot<W: Write>(&self, writer: &mut W) -> Result<()> {
+        writeln!(writer, "graph similar_ids {{")?;
+        for (index, id) in self.ids.iter().enumerate() {
+            let label = id.as_str().replace('\\', "\\\\").replace('"', "\\\"");
+            writeln!(writer, "    {} [label=\"{}\"];", index, label)?;
+        }
+        for edge in &self.edges {
+            writeln!(
+                writer,
+                "    {} -- {} [label=\"{}\"];",
+                edge.first, edge.second, edge.alignment.distance
+            )?;
+        }
+        writeln!(writer, "}}")?;
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::edit::align;
+    use super::*;
+    use proptest::prelude::*;
+
+    fn to_ids(ids: &[&str]) -> Vec<String> {
+        return ids.iter().map(|&id| String::from(id)).collect();
+    }
+
+    #[test]
+    fn transitive_clusters() {
+        let ids = to_ids(&["abcd", "wxyz", "abce", "qrst", "abfe", "wxyy", "mnop"]);
+        let graph = SimilarityGraph::build(&ids, Metric::Hamming, 1);
+        assert_eq!(3, graph.edges().len());
+        assert_eq!(
+            vec![
+                Cluster {
+                    ids: vec![0, 2, 4],
+                    common: String::from("ab")
+                },
+                Cluster {
+                    ids: vec![1, 5],
+                    common: String::from("wxy")
+                }
+            ],
+            graph.clusters()
+        );
+        assert!(SimilarityGraph::build(&ids, Metric::Hamming, 0)
+            .clusters()
+            .is_empty());
+    }
+
+    #[test]
+    fn clusters_of_different_lengths() {
+        let ids = to_ids(&["fghij", "fgij", "fguij", "klmno"]);
+        let clusters = SimilarityGraph::build(&ids, Metric::Levenshtein, 1).clusters();
+        assert_eq!(
+            vec![Cluster {
+                ids: vec![0, 1, 2],
+                common: String::from("fgij")
+            }],
+            clusters
+        );
+    }
+
+    #[test]
+    fn graph_as_dot() {
+        let ids = to_ids(&["ab\"c", "abbc", "xyz"]);
+        let mut dot = Vec::new();
+        SimilarityGraph::build(&ids, Metric::Hamming, 1)
+            .write_dot(&mut dot)
+            .unwrap();
+        assert_eq!(
+            "graph similar_ids {\n    \
+             0 [label=\"ab\\\"c\"];\n    \
+             1 [label=\"abbc\"];\n    \
+             2 [label=\"xyz\"];\n    \
+             0 -- 1 [label=\"1\"];\n\
+             }\n",
+            String::from_utf8(dot).unwrap()
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn clusters_partition_linked_ids(ids in prop::collection::vec("[a-c]{3}", 0..20)) {
+            let graph = SimilarityGraph::build(&ids, Metric::Hamming, 1);
+            let clusters = graph.clusters();
+            let cluster_of = |id: usize| clusters.iter().position(|cluster| cluster.ids.contains(&id));
+            for edge in graph.edges() {
+                prop_assert!(cluster_of(edge.first).is_some());
+                prop_assert_eq!(cluster_of(edge.first), cluster_of(edge.second));
+            }
+            let members: usize = clusters.iter().map(|cluster| cluster.ids.len()).sum();
+            let linked = (0..ids.len())
+                .filter(|&id| graph.edges().iter().any(|edge| edge.first == id || edge.second == id))
+                .count();
+            prop_assert_eq!(members, linked);
+            for cluster in &clusters {
+                for &id in &cluster.ids {
+                    prop_assert!(align(&cluster.common, &ids[id], Metric::Levenshtein, usize::MAX)
+                        .is_some_and(|alignment| alignment.common == cluster.common));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,456 @@
+use super::box_id::{join_units, BoxId, IdOptions};
+use super::near::find_near_box_ids;
+use error::{Error, Result};
+use std::collections::{HashMap, HashSet};
+
+/// How the distance between two box IDs is measured.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Metric {
+    /// Letters substituted, only between IDs of the same length.
+    Hamming,
+    /// Letters substituted, inserted or deleted.
+    Levenshtein,
+    /// Like `Levenshtein`, also counting two swapped neighbours as one edit, as
+    /// long as no unit is edited again. This is the restricted form of the
+    /// Damerau-Levenshtein distance: `ca` and `abc` are 3 edits apart, not 2.
+    OptimalStringAlignment,
+}
+
+/// One step turning an ID into another, each unit given as text.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Edit {
+    Keep(String),
+    Substitute {
+        from: String,
+        to: String,
+    },
+    /// A unit of the first ID missing from the second.
+    Delete(String),
+    /// A unit of the second ID missing from the first.
+    Insert(String),
+    /// Two neighbouring units of the first ID, swapped in the second.
+    Transpose(String, String),
+}
+
+/// The cheapest way to turn an ID into another.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alignment {
+    pub distance: usize,
+    pub edits: Vec<Edit>,
+    /// The units kept from one ID to the other, in order.
+    pub common: String,
+}
+
+/// An edit, by the indices of the units involved.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Step {
+    Keep(usize, usize),
+    Substitute(usize, usize),
+    Delete(usize),
+    Insert(usize),
+    /// The first of the two units swapped in each ID.
+    Transpose(usize, usize),
+}
+
+/// Aligns two IDs, or returns `None` if they're more than `max_distance` apart.
+pub fn align(first: &str, second: &str, metric: Metric, max_distance: usize) -> Option<Alignment> {
+    return align_box_ids(
+        &BoxId::new(first, IdOptions::default()),
+        &BoxId::new(second, IdOptions::default()),
+        metric,
+        max_distance,
+    );
+}
+
+/// Like `align`, comparing the IDs by their units.
+pub fn align_box_ids(
+    first: &BoxId,
+    second: &BoxId,
+    metric: Metric,
+    max_distance: usize,
+) -> Option<Alignment> {
+    let first = first.units();
+    let second = second.units();
+    let steps = match metric {
+        Metric::Hamming if first.len() != second.len() => return None,
+        Metric::Hamming => (0..first.len())
+            .map(|index| {
+                if first[index] == second[index] {
+                    Step::Keep(index, index)
+                } else {
+                    Step::Substitute(index, index)
+                }
+            })
+            .collect(),
+        _ if first.len().abs_diff(second.len()) > max_distance => return None,
+        _ => edit_script(&first, &second, metric == Metric::OptimalStringAlignment),
+    };
+
+    let text = |unit: &[u8]| join_units(&[unit]);
+    let mut common = Vec::new();
+    let mut edits = Vec::with_capacity(steps.len());
+    for step in steps {
+        edits.push(match step {
+            Step::Keep(i, _) => {
+                common.push(first[i]);
+                Edit::Keep(text(first[i]))
+            }
+            Step::Substitute(i, j) => Edit::Substitute {
+                from: text(first[i]),
+                to: text(second[j]),
+            },
+            Step::Delete(i) => Edit::Delete(text(first[i])),
+            Step::Insert(j) => Edit::Insert(text(second[j])),
+            Step::Transpose(i, _) => Edit::Transpose(text(first[i]), text(first[i + 1])),
+        });
+    }
+    let distance = edits.len() - common.len();
+    if distance > max_distance {
+        return None;
+    }
+    return Some(Alignment {
+        distance,
+        edits,
+        common: join_units(&common),
+    });
+}
+
+/// The units of `first` kept by a cheapest Levenshtein alignment with `second`.
+pub fn common_units<'a>(first: &[&'a [u8]], second: &[&[u8]]) -> Vec<&'a [u8]> {
+    return edit_script(first, second, false)
+        .into_iter()
+        .filter_map(|step| match step {
+            Step::Keep(i, _) => Some(first[i]),
+            _ => None,
+        })
+        .collect();
+}
+
+/// The steps of a cheapest alignment, from the usual dynamic programming
+/// table of distances between prefixes.
+fn edit_script(first: &[&[u8]], second: &[&[u8]], transpositions: bool) -> Vec<Step> {
+    let width = second.len() + 1;
+    let mut distances = vec![0usize; (first.len() + 1) * width];
+    for i in 0..=first.len() {
+        for j in 0..=second.len() {
+            distances[i * width + j] = if i == 0 || j == 0 {
+                i + j
+            } else {
+                let substitution = usize::from(first[i - 1] != second[j - 1]);
+                let mut distance = (distances[(i - 1) * width + j - 1] + substitution)
+                    .min(distances[(i - 1) * width + j] + 1)
+                    .min(distances[i * width + j - 1] + 1);
+                if transpositions && is_transposition(first, second, i, j) {
+                    distance = distance.min(distances[(i - 2) * width + j - 2] + 1);
+                }
+                distance
+            };
+        }
+    }
+
+    let mut steps = Vec::new();
+    let (mut i, mut j) = (first.len(), second.len());
+    while i > 0 || j > 0 {
+        let distance = distances[i * width + j];
+        if i > 0 && j > 0 {
+            let substitution = usize::from(first[i - 1] != second[j - 1]);
+            if distances[(i - 1) * width + j - 1] + substitution == distance {
+                steps.push(if substitution == 0 {
+                    Step::Keep(i - 1, j - 1)
+                } else {
+                    Step::Substitute(i - 1, j - 1)
+                });
+                i -= 1;
+                j -= 1;
+                continue;
+            }
+        }
+        if transpositions
+            && is_transposition(first, second, i, j)
+            && distances[(i - 2) * width + j - 2] + 1 == distance
+        {
+            steps.push(Step::Transpose(i - 2, j - 2));
+            i -= 2;
+            j -= 2;
+        } else if i > 0 && distances[(i - 1) * width + j] + 1 == distance {
+            steps.push(Step::Delete(i - 1));
+            i -= 1;
+        } else {
+            steps.push(Step::Insert(j - 1));
+            j -= 1;
+        }
+    }
+    steps.reverse();
+    return steps;
+}
+
+/// Whether the two units ending `first[..i]` are swapped at the end of `second[..j]`.
+fn is_transposition(first: &[&[u8]], second: &[&[u8]], i: usize, j: usize) -> bool {
+    return i > 1
+        && j > 1
+        && first[i - 1] == second[j - 2]
+        && first[i - 2] == second[j - 1]
+        && first[i - 1] != first[i - 2];
+}
+
+/// Two box IDs within the allowed distance of each other.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClosePair {
+    /// Indices of the two IDs, the first one being the lowest.
+    pub first: usize,
+    pub second: usize,
+    /// How to turn the first ID into the second.
+    pub alignment: Alignment,
+}
+
+/// Every ID obtained by deleting up to `deletions` units.
+fn deletion_neighbourhood<'a>(id: &[&'a [u8]], deletions: usize) -> HashSet<Vec<&'a [u8]>> {
+    let mut neighbourhood = HashSet::new();
+    let mut current = vec![id.to_vec()];
+    neighbourhood.insert(id.to_vec());
+    for _ in 0..deletions {
+        let mut next = Vec::new();
+        for variant in &current {
+            for position in 0..variant.len() {
+                let mut shorter = variant.clone();
+                shorter.remove(position);
+                if neighbourhood.insert(shorter.clone()) {
+                    next.push(shorter);
+                }
+            }
+        }
+        current = next;
+    }
+    return neighbourhood;
+}
+
+/// Finds every pair of IDs at most `max_distance` apart, ordered by their
+/// indices in `ids`.
+pub fn find_close_ids(ids: &[String], metric: Metric, max_distance: usize) -> Vec<ClosePair> {
+    let ids: Vec<BoxId> = ids
+        .iter()
+        .map(|id| BoxId::new(id, IdOptions::default()))
+        .collect();
+    return find_close_box_ids(&ids, metric, max_distance);
+}
+
+/// Like `find_close_ids`, comparing the IDs by their units.
+///
+/// With Hamming distance, this goes through `find_near_box_ids`. Otherwise
+/// every edit costs at most one deletion from each ID, so two close IDs always
+/// share an ID obtained by deleting up to `max_distance` units: IDs are
+/// indexed by all of those, and only compared when they share one.
+pub fn find_close_box_ids(ids: &[BoxId], metric: Metric, max_distance: usize) -> Vec<ClosePair> {
+    let pairs: Vec<(usize, usize)> = if metric == Metric::Hamming {
+        find_near_box_ids(ids, max_distance)
+            .into_iter()
+            .map(|duplicate| (duplicate.first, duplicate.second))
+            .collect()
+    } else {
+        let mut index: HashMap<Vec<&[u8]>, Vec<usize>> = HashMap::new();
+        let mut pairs = HashSet::new();
+        for (second, id) in ids.iter().enumerate() {
+            for variant in deletion_neighbourhood(&id.units(), max_distance) {
+                let bucket = index.entry(variant).or_default();
+                pairs.extend(bucket.iter().map(|&first| (first, second)));
+                bucket.push(second);
+            }
+        }
+        let mut pairs: Vec<(usize, usize)> = pairs.into_iter().collect();
+        pairs.sort_unstable();
+        pairs
+    };
+
+    return pairs
+        .into_iter()
+        .filter_map(|(first, second)| {
+            let alignment = align_box_ids(&ids[first], &ids[second], metric, max_distance)?;
+            Some(ClosePair {
+                first,
+                second,
+                alignment,
+            })
+        })
+        .collect();
+}
+
+/// Returns the common letters of the first two IDs at most `max_distance`
+/// apart, like `find_similar_ids` does for a single substituted letter.
+pub fn find_similar_ids_by(ids: &[String], metric: Metric, max_distance: usize) -> Result<String> {
+    return find_close_ids(ids, metric, max_distance)
+        .into_iter()
+        .next()
+        .map(|pair| pair.alignment.common)
+        .ok_or(Error::NoAnswer("no two box IDs are close enough"));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::box_id::{CaseFolding, Unit};
+    use super::*;
+    use proptest::prelude::*;
+
+    fn text(unit: &str) -> String {
+        return String::from(unit);
+    }
+
+    /// Replays the edits on the first ID, giving back both IDs.
+    fn replay(edits: &[Edit]) -> (String, String) {
+        let (mut first, mut second) = (String::new(), String::new());
+        for edit in edits {
+            match *edit {
+                Edit::Keep(ref unit) => {
+                    first.push_str(unit);
+                    second.push_str(unit);
+                }
+                Edit::Substitute { ref from, ref to } => {
+                    first.push_str(from);
+                    second.push_str(to);
+                }
+                Edit::Delete(ref unit) => first.push_str(unit),
+                Edit::Insert(ref unit) => second.push_str(unit),
+                Edit::Transpose(ref left, ref right) => {
+                    first.push_str(left);
+                    first.push_str(right);
+                    second.push_str(right);
+                    second.push_str(left);
+                }
+            }
+        }
+        return (first, second);
+    }
+
+    #[test]
+    fn align_dropped_and_duplicated_letters() {
+        let alignment = align("fghij", "fgij", Metric::Levenshtein, 1).unwrap();
+        assert_eq!(1, alignment.distance);
+        assert_eq!("fgij", alignment.common);
+        assert!(alignment.edits.contains(&Edit::Delete(text("h"))));
+
+        let alignment = align("fgij", "fgiij", Metric::Levenshtein, 1).unwrap();
+        assert_eq!(
+            vec![
+                Edit::Keep(text("f")),
+                Edit::Keep(text("g")),
+                Edit::Insert(text("i")),
+                Edit::Keep(text("i")),
+                Edit::Keep(text("j"))
+            ],
+            alignment.edits
+        );
+        assert_eq!(None, align("fghij", "fgij", Metric::Hamming, 3));
+        assert_eq!(None, align("abc", "a", Metric::Levenshtein, 1));
+    }
+
+    #[test]
+    fn align_transpositions() {
+        assert_eq!(
+            2,
+            align("abcd", "acbd", Metric::Levenshtein, 2)
+                .unwrap()
+                .distance
+        );
+        // Unrestricted Damerau-Levenshtein would swap `ca` then insert `b`.
+        let osa = align("ca", "abc", Metric::OptimalStringAlignment, 3).unwrap();
+        assert_eq!(3, osa.distance);
+        assert_eq!(None, align("ca", "abc", Metric::OptimalStringAlignment, 2));
+        let alignment = align("abcd", "acbd", Metric::OptimalStringAlignment, 1).unwrap();
+        assert_eq!(
+            vec![
+                Edit::Keep(text("a")),
+                Edit::Transpose(text("b"), text("c")),
+                Edit::Keep(text("d"))
+            ],
+            alignment.edits
+        );
+        assert_eq!("ad", alignment.common);
+    }
+
+    #[test]
+    fn align_graphemes() {
+        let options = IdOptions {
+            unit: Unit::Grapheme,
+            folding: CaseFolding::None,
+        };
+        let first = BoxId::new("xe\u{301}a", options);
+        let second = BoxId::new("xae\u{301}", options);
+        let alignment = align_box_ids(&first, &second, Metric::OptimalStringAlignment, 1).unwrap();
+        assert_eq!(
+            vec![
+                Edit::Keep(text("x")),
+                Edit::Transpose(text("e\u{301}"), text("a"))
+            ],
+            alignment.edits
+        );
+        assert_eq!(
+            None,
+            align(
+                "xe\u{301}a",
+                "xae\u{301}",
+                Metric::OptimalStringAlignment,
+                1
+            )
+        );
+    }
+
+    #[test]
+    fn close_ids_of_different_lengths() {
+        let ids: Vec<String> = vec!["abcde", "fghij", "fgij", "klmno", "fguij", "kmlno"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let pairs: Vec<(usize, usize, usize)> = find_close_ids(&ids, Metric::Levenshtein, 1)
+            .iter()
+            .map(|pair| (pair.first, pair.second, pair.alignment.distance))
+            .collect();
+        assert_eq!(vec![(1, 2, 1), (1, 4, 1), (2, 4, 1)], pairs);
+        let pairs: Vec<(usize, usize)> = find_close_ids(&ids, Metric::OptimalStringAlignment, 1)
+            .iter()
+            .map(|pair| (pair.first, pair.second))
+            .collect();
+        assert_eq!(vec![(1, 2), (1, 4), (2, 4), (3, 5)], pairs);
+        assert_eq!(
+            "fgij",
+            find_similar_ids_by(&ids, Metric::Hamming, 1).unwrap()
+        );
+        assert!(find_similar_ids_by(&ids[..2], Metric::OptimalStringAlignment, 2).is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn alignments_replay_both_ids(
+            first in "[a-c]{0,6}",
+            second in "[a-c]{0,6}",
+            osa in any::<bool>(),
+        ) {
+            let metric = if osa { Metric::OptimalStringAlignment } else { Metric::Levenshtein };
+            let alignment = align(&first, &second, metric, 12).unwrap();
+            prop_assert_eq!(replay(&alignment.edits), (first.clone(), second.clone()));
+            prop_assert!(alignment.distance <= first.len().max(second.len()));
+            let reversed = align(&second, &first, metric, 12).unwrap();
+            prop_assert_eq!(alignment.distance, reversed.distance);
+        }
+
+        #[test]
+        fn close_ids_match_pairwise_alignment(
+            ids in prop::collection::vec("[a-c]{0,5}", 0..15),
+            max_distance in 0usize..3,
+            osa in any::<bool>(),
+        ) {
+            let metric = if osa { Metric::OptimalStringAlignment } else { Metric::Levenshtein };
+            let mut expected = Vec::new();
+            for first in 0..ids.len() {
+                for second in first + 1..ids.len() {
+                    if align(&ids[first], &ids[second], metric, max_distance).is_some() {
+                        expected.push((first, second));
+                    }
+                }
+            }
+            let pairs: Vec<(usize, usize)> = find_close_ids(&ids, metric, max_distance)
+                .iter()
+                .map(|pair| (pair.first, pair.second))
+                .collect();
+            prop_assert_eq!(pairs, expected);
+        }
+    }
+}
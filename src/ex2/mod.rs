@@ -0,0 +1,257 @@
+use error::{Error, Result};
+use input::Input;
+use solution::Solution;
+use std::convert::TryFrom;
+
+mod box_id;
+mod checksum;
+mod cluster;
+mod edit;
+mod near;
+
+pub use self::box_id::{parse_box_ids, BoxId, CaseFolding, IdOptions, Unit};
+pub use self::checksum::{Bucket, Checksum, ChecksumReport, Combine, CombineFn};
+pub use self::cluster::{Cluster, SimilarityGraph};
+pub use self::edit::{
+    align, align_box_ids, find_close_box_ids, find_close_ids, find_similar_ids_by, Alignment,
+    ClosePair, Edit, Metric,
+};
+pub use self::near::{find_near_box_ids, find_near_duplicates, NearDuplicate};
+
+/// Returns whether the ID contains a letter exactly twice, and one exactly three times.
+pub fn get_product_checksum(id: &str) -> (i32, i32) {
+    let mut doubles = 0;
+    let mut triplets = 0;
+    let id = BoxId::new(id, IdOptions::default());
+    for value in id.unit_counts().values() {
+        if *value == 2 {
+            doubles = 1;
+        }
+        if *value == 3 {
+            triplets = 1;
+        }
+    }
+
+    return (doubles, triplets);
+}
+
+/// Multiplies the number of IDs with a doubled letter by the number with a tripled one.
+pub fn calculate_checksum(ids: Vec<String>) -> i32 {
+    let val =
+        ids.iter()
+            .map(|id| get_product_checksum(id))
+            .fold((0, 0), |acc, (doubles, triplets)| {
+                return (acc.0 + doubles, acc.1 + triplets);
+            });
+    return val.0 * val.1;
+}
+
+/// Returns the letters the two IDs have in common if they differ by at most one letter.
+/// IDs of different lengths are never similar, see `align` to compare those.
+pub fn are_ids_similar<'a>(first: &'a str, second: &'a str) -> Option<String> {
+    return align(first, second, Metric::Hamming, 1).map(|alignment| alignment.common);
+}
+
+/// Splits the input into box IDs.
+pub fn parse_ids(content: &str) -> Vec<String> {
+    let split_ids = content.split_whitespace();
+    let mut ids = Vec::new();
+    for id in split_ids {
+        ids.push(id.to_string());
+    }
+    return ids;
+}
+
+/// Returns the common letters of the first two IDs differing by a single letter.
+pub fn find_base_id(mut ids: Vec<String>) -> Result<String> {
+    let el = ids
+        .pop()
+        .ok_or(Error::NoAnswer("no two box IDs differ by a single letter"))?;
+
+    let mut new_ids: Vec<String> = Vec::new();
+    for id in ids {
+        if let Some(matching_id) = are_ids_similar(&id, &el) {
+            return Ok(matching_id);
+        } else {
+            new_ids.push(id);
+        }
+    }
+    return find_base_id(new_ids);
+}
+
+/// Returns the common letters of two IDs differing by at most one letter,
+/// indexing the IDs instead of comparing every pair of them.
+pub fn find_similar_ids(ids: &[String]) -> Result<String> {
+    let ids: Vec<BoxId> = ids
+        .iter()
+        .map(|id| BoxId::new(id, IdOptions::default()))
+        .collect();
+    return find_similar_box_ids(&ids);
+}
+
+/// Like `find_similar_ids`, comparing the IDs by their units.
+pub fn find_similar_box_ids(ids: &[BoxId]) -> Result<String> {
+    return find_near_box_ids(ids, 1)
+        .into_iter()
+        .next()
+        .map(|duplicate| duplicate.common)
+        .ok_or(Error::NoAnswer("no two box IDs differ by a single letter"));
+}
+
+/// Checksum of the box IDs listed in `input`.
+pub fn solve_exercise_1(input: &Input) -> Result<i32> {
+    return Exercise2::solve_part_1(input);
+}
+
+/// Letters shared by the two prototype fabric boxes listed in `input`.
+pub fn solve_exercise_2(input: &Input) -> Result<String> {
+    return Exercise2::solve_part_2(input);
+}
+
+/// Day 2: Inventory Management System.
+pub struct Exercise2;
+
+impl Solution for Exercise2 {
+    type Parsed<'a> = Vec<BoxId>;
+    type Answer1 = i32;
+    type Answer2 = String;
+
+    fn parse(content: &str) -> Result<Vec<BoxId>> {
+        Ok(parse_box_ids(content, IdOptions::default()))
+    }
+
+    fn part_1(ids: &Vec<BoxId>) -> Result<i32> {
+        let report = Checksum::new(&[2, 3], Combine::Product).compute_box_ids(ids)?;
+        i32::try_from(report.value)
+            .map_err(|_| Error::Invalid(String::from("the checksum overflows")))
+    }
+
+    fn part_2(ids: &Vec<BoxId>) -> Result<String> {
+        find_similar_box_ids(ids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    #[test]
+    fn get_ids_should_work() {
+        assert_eq!(
+            Some(String::from("fgij")),
+            are_ids_similar("fghij", "fguij")
+        );
+        assert_eq!(None, are_ids_similar("aghij", "fguij"));
+    }
+
+    #[test]
+    fn ids_of_different_lengths_are_not_similar() {
+        assert_eq!(None, are_ids_similar("fghij", "fgh"));
+        assert_eq!(None, are_ids_similar("fgh", "fghij"));
+    }
+
+    #[test]
+    fn checksum_with_doubles_and_triplets() {
+        assert_eq!((1, 1), get_product_checksum("bababc"))
+    }
+
+    #[test]
+    fn several_doubles() {
+        assert_eq!((1, 0), get_product_checksum("aabcdd"))
+    }
+
+    #[test]
+    fn similar_ids() {
+        assert_eq!(
+            "fgij",
+            find_base_id(vec![
+                String::from("abcde"),
+                String::from("fghij"),
+                String::from("klmno"),
+                String::from("pqrst"),
+                String::from("fguij"),
+                String::from("axcye"),
+                String::from("wvxyz"),
+            ])
+            .unwrap()
+        )
+    }
+
+    #[test]
+    fn no_similar_ids() {
+        assert!(find_base_id(vec![String::from("abcde"), String::from("fghij")]).is_err());
+    }
+
+    #[test]
+    fn similar_ids_from_index() {
+        let ids: Vec<String> = vec!["abcde", "fghij", "klmno", "pqrst", "fguij", "axcye"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        assert_eq!("fgij", find_similar_ids(&ids).unwrap());
+        assert!(find_similar_ids(&ids[..4]).is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn similar_ids_match_pairwise_comparison(ids in prop::collection::vec("[a-c]{1,4}", 0..20)) {
+            let mut common_letters = Vec::new();
+            for (index, first) in ids.iter().enumerate() {
+                for second in &ids[index + 1..] {
+                    if first.len() == second.len() {
+                        common_letters.extend(are_ids_similar(first, second));
+                    }
+                }
+            }
+            match find_similar_ids(&ids) {
+                Ok(letters) => prop_assert!(common_letters.contains(&letters)),
+                Err(_) => prop_assert!(common_letters.is_empty()),
+            }
+        }
+    }
+
+    #[test]
+    fn similar_ids_by_grapheme_and_case() {
+        let options = IdOptions {
+            unit: Unit::Grapheme,
+            folding: CaseFolding::Unicode,
+        };
+        // The accented IDs are 6 chars long but only 5 graphemes.
+        let ids = parse_box_ids("Cafe\u{301}x bcdef cAFE\u{301}y abcde", options);
+        assert_eq!("cafe\u{301}", find_similar_box_ids(&ids).unwrap());
+        let default = parse_box_ids(
+            "Cafe\u{301}x bcdef cAFE\u{301}y abcde",
+            IdOptions::default(),
+        );
+        assert!(find_similar_box_ids(&default).is_err());
+    }
+
+    #[test]
+    fn checksum_of_list() {
+        assert_eq!(
+            12,
+            calculate_checksum(vec![
+                String::from("abcdef"),
+                String::from("bababc"),
+                String::from("abbcde"),
+                String::from("abcccd"),
+                String::from("aabcdd"),
+                String::from("abcdee"),
+                String::from("ababab"),
+            ])
+        )
+    }
+
+    #[test]
+    fn exercise_2_1() {
+        assert_eq!(5478, solve_exercise_1(&Input::bundled(2)).unwrap())
+    }
+
+    #[test]
+    fn exercise_2_2() {
+        assert_eq!(
+            String::from("qyzphxoiseldjrntfygvdmanu"),
+            solve_exercise_2(&Input::bundled(2)).unwrap()
+        )
+    }
+}
@@ -0,0 +1,193 @@
+use super::box_id::{join_units, BoxId, IdOptions};
+use std::collections::HashMap;
+
+/// Two box IDs of the same length differing by at most the allowed distance.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NearDuplicate {
+    /// Indices of the two IDs, the first one being the lowest.
+    pub first: usize,
+    pub second: usize,
+    /// Positions of the units that differ, in order.
+    pub positions: Vec<usize>,
+    /// The units both IDs share, in order.
+    pub common: String,
+}
+
+/// The length of an ID, one of its segments and the units in that segment.
+type SegmentKey<'a> = (usize, usize, &'a [&'a [u8]]);
+
+/// The range of letters covered by `segment` when splitting `length` letters
+/// into `segments` parts as even as possible.
+fn segment_range(length: usize, segments: usize, segment: usize) -> (usize, usize) {
+    return (
+        segment * length / segments,
+        (segment + 1) * length / segments,
+    );
+}
+
+/// Finds every pair of IDs differing by at most `distance` letters, ordered by
+/// their indices in `ids`. IDs of different lengths are never paired.
+pub fn find_near_duplicates(ids: &[String], distance: usize) -> Vec<NearDuplicate> {
+    let ids: Vec<BoxId> = ids
+        .iter()
+        .map(|id| BoxId::new(id, IdOptions::default()))
+        .collect();
+    return find_near_box_ids(&ids, distance);
+}
+
+/// Like `find_near_duplicates`, comparing the IDs by their units.
+///
+/// Each ID is split into `distance + 1` segments: two IDs can't differ in every
+/// segment without differing by more than `distance` units, so they are only
+/// compared when they share at least one segment. A pair is only compared from
+/// the first segment they share, so it is never compared twice.
+pub fn find_near_box_ids(ids: &[BoxId], distance: usize) -> Vec<NearDuplicate> {
+    let letters: Vec<Vec<&[u8]>> = ids.iter().map(BoxId::units).collect();
+    let segments = distance + 1;
+    let mut index: HashMap<SegmentKey, Vec<usize>> = HashMap::new();
+    let mut duplicates = Vec::new();
+
+    for (second, id) in letters.iter().enumerate() {
+        let length = id.len();
+        for segment in 0..segments {
+            let (start, end) = segment_range(length, segments, segment);
+            let bucket = index.entry((length, segment, &id[start..end])).or_default();
+            for &first in bucket.iter() {
+                let other = &letters[first];
+                let shared_before = (0..segment).any(|earlier| {
+                    let (start, end) = segment_range(length, segments, earlier);
+                    return id[start..end] == other[start..end];
+                });
+                if !shared_before {
+                    duplicates.extend(compare(first, other, second, id, distance));
+                }
+            }
+            bucket.push(second);
+        }
+    }
+    duplicates.sort_unstable_by_key(|duplicate| (duplicate.first, duplicate.second));
+    return duplicates;
+}
+
+/// Compares two IDs of the same length, giving up past `distance` differences.
+fn compare(
+    first: usize,
+    first_id: &[&[u8]],
+    second: usize,
+    second_id: &[&[u8]],
+    distance: usize,
+) -> Option<NearDuplicate> {
+    let mut positions = Vec::new();
+    let mut common = Vec::new();
+    for (position, (&first_letter, &second_letter)) in first_id.iter().zip(second_id).enumerate() {
+        if first_letter == second_letter {
+            common.push(first_letter);
+        } else if positions.len() == distance {
+            return None;
+        } else {
+            positions.push(position);
+        }
+    }
+    return Some(NearDuplicate {
+        first,
+        second,
+        positions,
+        common: join_units(&common),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::box_id::{CaseFolding, Unit};
+    use super::*;
+    use input::Input;
+    use proptest::prelude::*;
+
+    fn to_ids(ids: &[&str]) -> Vec<String> {
+        return ids.iter().map(|&id| String::from(id)).collect();
+    }
+
+    #[test]
+    fn example_near_duplicates() {
+        let ids = to_ids(&[
+            "abcde", "fghij", "klmno", "pqrst", "fguij", "axcye", "wvxyz",
+        ]);
+        assert_eq!(
+            vec![NearDuplicate {
+                first: 1,
+                second: 4,
+                positions: vec![2],
+                common: String::from("fgij")
+            }],
+            find_near_duplicates(&ids, 1)
+        );
+        let pairs: Vec<(usize, usize, Vec<usize>)> = find_near_duplicates(&ids, 2)
+            .into_iter()
+            .map(|duplicate| (duplicate.first, duplicate.second, duplicate.positions))
+            .collect();
+        assert_eq!(vec![(0, 5, vec![1, 3]), (1, 4, vec![2])], pairs);
+    }
+
+    #[test]
+    fn exact_and_mismatched_lengths() {
+        let ids = to_ids(&["abc", "abc", "ab", "abd", ""]);
+        let pairs: Vec<(usize, usize)> = find_near_duplicates(&ids, 0)
+            .into_iter()
+            .map(|duplicate| (duplicate.first, duplicate.second))
+            .collect();
+        assert_eq!(vec![(0, 1)], pairs);
+        // More segments than letters still finds every pair.
+        assert_eq!(3, find_near_duplicates(&ids, 5).len());
+    }
+
+    #[test]
+    fn bundled_near_duplicates() {
+        let ids = super::super::parse_ids(&Input::bundled(2).read().unwrap());
+        let duplicates = find_near_duplicates(&ids, 1);
+        assert_eq!(1, duplicates.len());
+        assert_eq!("qyzphxoiseldjrntfygvdmanu", duplicates[0].common);
+    }
+
+    #[test]
+    fn near_duplicates_by_grapheme() {
+        let options = IdOptions {
+            unit: Unit::Grapheme,
+            folding: CaseFolding::Unicode,
+        };
+        // Only the accent differs, which makes two chars but a single grapheme.
+        let ids = vec![
+            BoxId::new("Cafe\u{301}s", options),
+            BoxId::new("cafes", options),
+        ];
+        assert!(
+            find_near_duplicates(&[String::from("cafe\u{301}s"), String::from("cafes")], 1)
+                .is_empty()
+        );
+        let duplicates = find_near_box_ids(&ids, 1);
+        assert_eq!(1, duplicates.len());
+        assert_eq!(
+            (vec![3], "cafs"),
+            (duplicates[0].positions.clone(), &*duplicates[0].common)
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn near_duplicates_match_pairwise_comparison(
+            ids in prop::collection::vec("[a-c]{0,5}", 0..25),
+            distance in 0usize..4,
+        ) {
+            let ids: Vec<BoxId> = ids.iter().map(|id| BoxId::new(id, IdOptions::default())).collect();
+            let letters: Vec<Vec<&[u8]>> = ids.iter().map(BoxId::units).collect();
+            let mut expected = Vec::new();
+            for first in 0..ids.len() {
+                for second in first + 1..ids.len() {
+                    if letters[first].len() == letters[second].len() {
+                        expected.extend(compare(first, &letters[first], second, &letters[second], distance));
+                    }
+                }
+            }
+            prop_assert_eq!(find_near_box_ids(&ids, distance), expected);
+        }
+    }
+}
@@ -1,11 +1,18 @@
+use error::{parse_field, Error, Result};
+use input::Input;
+use solution::Solution;
 use std::collections::HashMap;
-use std::fs;
 
+const CLAIM: &str = "a claim such as `#123 @ 3,2: 5x4`";
+
+/// A rectangle of fabric claimed by an elf, as in `#123 @ 3,2: 5x4`.
 #[derive(Debug)]
-struct Claim<'a> {
-    id: &'a str,
-    coordinates: (u16, u16),
-    size: (u16, u16),
+pub struct Claim<'a> {
+    pub id: &'a str,
+    /// Inches from the left and top edges of the fabric.
+    pub coordinates: (u16, u16),
+    /// Width and height, in inches.
+    pub size: (u16, u16),
 }
 impl<'a> PartialEq for Claim<'a> {
     fn eq(&self, other: &Claim) -> bool {
@@ -13,37 +20,91 @@ impl<'a> PartialEq for Claim<'a> {
     }
 }
 
+fn next_field<'a, I: Iterator<Item = &'a str>>(claim: &'a str, fields: &mut I) -> Result<&'a str> {
+    fields.next().ok_or_else(|| Error::missing(claim, CLAIM))
+}
+
+fn parse_pair(claim: &str, field: &str, separator: char) -> Result<(u16, u16)> {
+    let mut values = field.split(separator);
+    match (values.next(), values.next(), values.next()) {
+        (Some(first), Some(second), None) => Ok((
+            parse_field(claim, first, CLAIM)?,
+            parse_field(claim, second, CLAIM)?,
+        )),
+        _ => Err(Error::parse(claim, field, CLAIM)),
+    }
+}
+
+impl<'a> Claim<'a> {
+    /// Whether both claims share at least one square inch.
+    pub fn overlaps(&self, other: &Claim) -> bool {
+        let intersect = |start: u16, size: u16, other_start: u16, other_size: u16| {
+            let end = u32::from(start) + u32::from(size);
+            let other_end = u32::from(other_start) + u32::from(other_size);
+            u32::from(start.max(other_start)) < end.min(other_end)
+        };
+        intersect(
+            self.coordinates.0,
+            self.size.0,
+            other.coordinates.0,
+            other.size.0,
+        ) && intersect(
+            self.coordinates.1,
+            self.size.1,
+            other.coordinates.1,
+            other.size.1,
+        )
+    }
+}
+
 // learn regex, maybe?
-fn parse_claim<'a>(claim: &'a str) -> Claim<'a> {
+/// Parses a claim such as `#123 @ 3,2: 5x4`.
+pub fn parse_claim(claim: &str) -> Result<Claim<'_>> {
     let mut split_str = claim.split_whitespace();
 
-    let (_, id) = split_str.next().unwrap().split_at(1);
+    let id_field = next_field(claim, &mut split_str)?;
+    if !id_field.starts_with('#') || id_field.len() == 1 {
+        return Err(Error::parse(claim, id_field, CLAIM));
+    }
+    let (_, id) = id_field.split_at(1);
+
+    let at = next_field(claim, &mut split_str)?;
+    if at != "@" {
+        return Err(Error::parse(claim, at, CLAIM));
+    }
 
-    // drop the @
-    split_str.next();
+    let coordinates_field = next_field(claim, &mut split_str)?;
+    if !coordinates_field.ends_with(':') {
+        return Err(Error::parse(claim, coordinates_field, CLAIM));
+    }
+    let coordinates_field = &coordinates_field[..coordinates_field.len() - 1];
+    let (x, y) = parse_pair(claim, coordinates_field, ',')?;
 
-    let mut coordinates = split_str
-        .next()
-        .expect(claim)
-        .split(":")
-        .next()
-        .unwrap()
-        .split(",");
-    let x = coordinates.next().unwrap().parse().unwrap();
-    let y = coordinates.next().unwrap().parse().unwrap();
+    let size_field = next_field(claim, &mut split_str)?;
+    let (width, height) = parse_pair(claim, size_field, 'x')?;
+    if x.checked_add(width).is_none() || y.checked_add(height).is_none() {
+        return Err(Error::parse(
+            claim,
+            size_field,
+            "a claim that fits on a 65535 inches wide fabric",
+        ));
+    }
 
-    let mut size = split_str.next().unwrap().split("x");
-    let width = size.next().unwrap().parse().unwrap();
-    let height = size.next().unwrap().parse().unwrap();
+    if let Some(extra) = split_str.next() {
+        return Err(Error::parse(claim, extra, CLAIM));
+    }
 
-    Claim {
+    Ok(Claim {
         id,
         coordinates: (x, y),
         size: (width, height),
-    }
+    })
 }
 
-fn set_covered_fabric(claim: &Claim, fabric: &mut HashMap<String, u16>) {
+/// How many claims cover each square inch of fabric, keyed by `"x,y"`.
+pub type Fabric = HashMap<String, u16>;
+
+fn set_covered_fabric(claim: &Claim, fabric: &mut Fabric) {
     let mut x = claim.coordinates.0;
     while x < claim.coordinates.0 + claim.size.0 {
         let mut y = claim.coordinates.1;
@@ -57,16 +118,18 @@ fn set_covered_fabric(claim: &Claim, fabric: &mut HashMap<String, u16>) {
     }
 }
 
-fn get_covered_fabric_for_claims(claims: &mut Vec<Claim>) -> HashMap<String, u16> {
-    let mut fabric: HashMap<String, u16> = HashMap::new();
+/// Counts how many claims cover each square inch of fabric.
+pub fn get_covered_fabric_for_claims(claims: &[Claim]) -> Fabric {
+    let mut fabric: Fabric = HashMap::new();
     for claim in claims {
-        set_covered_fabric(&claim, &mut fabric);
+        set_covered_fabric(claim, &mut fabric);
     }
     return fabric;
 }
 
-fn find_fabric_for_claims(mut claims: Vec<Claim>) -> u32 {
-    let fabric = get_covered_fabric_for_claims(&mut claims);
+/// Counts the square inches of fabric covered by two claims or more.
+pub fn find_fabric_for_claims(claims: &[Claim]) -> u32 {
+    let fabric = get_covered_fabric_for_claims(claims);
 
     let mut count = 0;
     for (_, times_covered) in fabric {
@@ -77,7 +140,7 @@ fn find_fabric_for_claims(mut claims: Vec<Claim>) -> u32 {
     return count;
 }
 
-fn is_claim_overlapped(claim: &Claim, fabric: &HashMap<String, u16>) -> bool {
+fn is_claim_overlapped(claim: &Claim, fabric: &Fabric) -> bool {
     let mut is_overlapped = false;
     let mut x = claim.coordinates.0;
     while x < claim.coordinates.0 + claim.size.0 {
@@ -94,63 +157,162 @@ fn is_claim_overlapped(claim: &Claim, fabric: &HashMap<String, u16>) -> bool {
     return is_overlapped;
 }
 
-fn find_claim_with_no_overlap<'a>(mut claims: Vec<Claim<'a>>) -> &'a str {
-    let fabric = get_covered_fabric_for_claims(&mut claims);
+/// Returns the id of the only claim that doesn't overlap any other.
+pub fn find_claim_with_no_overlap<'a>(claims: &[Claim<'a>]) -> Result<&'a str> {
+    let fabric = get_covered_fabric_for_claims(claims);
 
     for claim in claims {
-        let is_overlapped = is_claim_overlapped(&claim, &fabric);
+        let is_overlapped = is_claim_overlapped(claim, &fabric);
 
         if !is_overlapped {
-            return claim.id;
+            return Ok(claim.id);
         }
     }
 
-    panic!("No overlapping claim found");
+    return Err(Error::NoAnswer("every claim overlaps another one"));
+}
+
+/// Counts the square inches of fabric covered by two claims or more, sweeping
+/// across the fabric from left to right instead of mapping every square inch.
+pub fn count_overlapping_fabric(claims: &[Claim]) -> u32 {
+    // At a given x, claims ending there are removed before new ones are added.
+    let mut events: Vec<(u32, bool, &Claim)> = Vec::new();
+    for claim in claims
+        .iter()
+        .filter(|claim| claim.size.0 > 0 && claim.size.1 > 0)
+    {
+        let x = u32::from(claim.coordinates.0);
+        events.push((x, true, claim));
+        events.push((x + u32::from(claim.size.0), false, claim));
+    }
+    events.sort_by_key(|&(x, is_start, _)| (x, is_start));
+
+    let height = claims
+        .iter()
+        .map(|claim| usize::from(claim.coordinates.1) + usize::from(claim.size.1))
+        .max()
+        .unwrap_or(0);
+    let mut claims_per_row: Vec<u32> = vec![0; height];
+    let mut overlapped_rows = 0;
+    let mut area = 0;
+    let mut previous_x = 0;
+    for (x, is_start, claim) in events {
+        area += (x - previous_x) * overlapped_rows;
+        previous_x = x;
+        let top = usize::from(claim.coordinates.1);
+        for row in &mut claims_per_row[top..top + usize::from(claim.size.1)] {
+            if is_start {
+                *row += 1;
+                if *row == 2 {
+                    overlapped_rows += 1;
+                }
+            } else {
+                if *row == 2 {
+                    overlapped_rows -= 1;
+                }
+                *row -= 1;
+            }
+        }
+    }
+    return area;
 }
 
-pub fn solve_exercise_2() -> String {
-    let content = fs::read_to_string("./inputs/input3").unwrap();
-    let split_claims = content.trim().lines();
-    let mut claims = Vec::new();
-    for claim in split_claims {
-        claims.push(claim.to_string());
+/// Returns the id of the first claim that doesn't overlap any other, only
+/// comparing claims whose horizontal extents intersect.
+pub fn find_intact_claim<'a>(claims: &[Claim<'a>]) -> Result<&'a str> {
+    let mut by_left_edge: Vec<usize> = (0..claims.len()).collect();
+    by_left_edge.sort_by_key(|&index| claims[index].coordinates.0);
+
+    let mut is_overlapped = vec![false; claims.len()];
+    let mut active: Vec<usize> = Vec::new();
+    for index in by_left_edge {
+        let claim = &claims[index];
+        active.retain(|&other| {
+            let other = &claims[other];
+            u32::from(other.coordinates.0) + u32::from(other.size.0)
+                > u32::from(claim.coordinates.0)
+        });
+        for &other in &active {
+            if claim.overlaps(&claims[other]) {
+                is_overlapped[index] = true;
+                is_overlapped[other] = true;
+            }
+        }
+        active.push(index);
     }
-    let claims: Vec<Claim> = claims.iter().map(|claim| parse_claim(claim)).collect();
 
-    return String::from(find_claim_with_no_overlap(claims));
+    claims
+        .iter()
+        .zip(is_overlapped)
+        .find(|&(_, is_overlapped)| !is_overlapped)
+        .map(|(claim, _)| claim.id)
+        .ok_or(Error::NoAnswer("every claim overlaps another one"))
+}
+
+/// Parses one claim per line.
+pub fn parse_claims(content: &str) -> Result<Vec<Claim<'_>>> {
+    content
+        .lines()
+        .enumerate()
+        .filter(|&(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| parse_claim(line).map_err(|error| error.on_line(index + 1)))
+        .collect()
+}
+
+/// Id of the only claim of `input` overlapping no other.
+pub fn solve_exercise_2(input: &Input) -> Result<String> {
+    return Exercise3::solve_part_2(input);
+}
+
+/// Square inches of fabric within two or more claims of `input`.
+pub fn solve_exercise_1(input: &Input) -> Result<u32> {
+    return Exercise3::solve_part_1(input);
 }
 
-pub fn solve_exercise_1() -> u32 {
-    let content = fs::read_to_string("./inputs/input3").unwrap();
-    let split_claims = content.trim().lines();
-    let mut claims = Vec::new();
-    for claim in split_claims {
-        claims.push(claim.to_string());
+/// Day 3: No Matter How You Slice It.
+pub struct Exercise3;
+
+impl Solution for Exercise3 {
+    type Parsed<'a> = Vec<Claim<'a>>;
+    type Answer1 = u32;
+    type Answer2 = String;
+
+    fn parse(content: &str) -> Result<Vec<Claim<'_>>> {
+        parse_claims(content)
+    }
+
+    fn part_1(claims: &Vec<Claim>) -> Result<u32> {
+        Ok(count_overlapping_fabric(claims))
     }
-    let claims: Vec<Claim> = claims.iter().map(|claim| parse_claim(claim)).collect();
 
-    return find_fabric_for_claims(claims);
+    fn part_2(claims: &Vec<Claim>) -> Result<String> {
+        find_intact_claim(claims).map(String::from)
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn solve_exercise_3_2() {
-        assert_eq!(String::from("346"), solve_exercise_2());
+        assert_eq!(
+            String::from("346"),
+            solve_exercise_2(&Input::bundled(3)).unwrap()
+        );
     }
 
     #[test]
     fn solve_exercise_3_1() {
-        assert_eq!(107043, solve_exercise_1())
+        assert_eq!(107043, solve_exercise_1(&Input::bundled(3)).unwrap())
     }
 
     #[test]
     fn find_claim_with_no_overlap_test() {
         assert_eq!(
             "3",
-            find_claim_with_no_overlap(vec![
+            find_claim_with_no_overlap(&[
                 Claim {
                     id: "1",
                     coordinates: (1, 3),
@@ -167,6 +329,7 @@ mod test {
                     size: (2, 2)
                 },
             ])
+            .unwrap()
         )
     }
 
@@ -174,7 +337,7 @@ mod test {
     fn test_fabric_for_claims() {
         assert_eq!(
             4,
-            find_fabric_for_claims(vec![
+            find_fabric_for_claims(&[
                 Claim {
                     id: "1",
                     coordinates: (1, 3),
@@ -193,6 +356,61 @@ mod test {
             ])
         )
     }
+    fn example_claims() -> Vec<Claim<'static>> {
+        vec![
+            Claim {
+                id: "1",
+                coordinates: (1, 3),
+                size: (4, 4),
+            },
+            Claim {
+                id: "2",
+                coordinates: (3, 1),
+                size: (4, 4),
+            },
+            Claim {
+                id: "3",
+                coordinates: (5, 5),
+                size: (2, 2),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_sweep_example() {
+        assert_eq!(4, count_overlapping_fabric(&example_claims()));
+        assert_eq!("3", find_intact_claim(&example_claims()).unwrap());
+        assert!(find_intact_claim(&example_claims()[..2]).is_err());
+    }
+
+    fn arbitrary_claims() -> BoxedStrategy<Vec<(u16, u16, u16, u16)>> {
+        prop::collection::vec((0u16..20, 0u16..20, 0u16..8, 0u16..8), 0..30).boxed()
+    }
+
+    fn to_claims<'a>(ids: &'a [String], rectangles: &[(u16, u16, u16, u16)]) -> Vec<Claim<'a>> {
+        ids.iter()
+            .zip(rectangles)
+            .map(|(id, &(x, y, width, height))| Claim {
+                id,
+                coordinates: (x, y),
+                size: (width, height),
+            })
+            .collect()
+    }
+
+    proptest! {
+        #[test]
+        fn sweep_matches_fabric_map(rectangles in arbitrary_claims()) {
+            let ids: Vec<String> = (0..rectangles.len()).map(|id| id.to_string()).collect();
+            let claims = to_claims(&ids, &rectangles);
+            prop_assert_eq!(count_overlapping_fabric(&claims), find_fabric_for_claims(&claims));
+            prop_assert_eq!(
+                find_intact_claim(&claims).ok(),
+                find_claim_with_no_overlap(&claims).ok()
+            );
+        }
+    }
+
     #[test]
     fn test_parse_claim() {
         assert_eq!(
@@ -201,7 +419,27 @@ mod test {
                 coordinates: (3, 2),
                 size: (5, 4)
             },
-            parse_claim("#123 @ 3,2: 5x4")
+            parse_claim("#123 @ 3,2: 5x4").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_claims() {
+        let positions = |content: &str| match parse_claims(content) {
+            Err(Error::Parse(error)) => (error.line, error.column, error.text),
+            result => panic!("unexpected result {:?}", result),
+        };
+        assert_eq!(
+            (2, 8, String::from("y")),
+            positions("#1 @ 1,1: 2x2\n#2 @ 3,y: 5x4")
+        );
+        assert_eq!((1, 1, String::from("123")), positions("123 @ 3,2: 5x4"));
+        assert_eq!((1, 8, String::from("3,2")), positions("#123 @ 3,2 5x4"));
+        assert_eq!((1, 15, String::new()), positions("#123 @ 3,2: 5x"));
+        assert_eq!((1, 12, String::new()), positions("#123 @ 3,2:"));
+        assert_eq!(
+            (1, 17, String::from("extra")),
+            positions("#123 @ 3,2: 5x4 extra")
         );
     }
 
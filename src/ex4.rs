@@ -1,107 +1,129 @@
 use chrono::prelude::*;
 use chrono::Duration;
+use error::{parse_field, Error, Result};
+use input::Input;
+use solution::Solution;
 use std::collections::HashMap;
-use std::fs;
 
+const RECORD: &str = "a record such as `[1518-11-01 00:05] falls asleep`";
+
+/// What happened in a record of the guards' log.
 #[derive(PartialEq, Debug)]
-enum RecordType {
+pub enum RecordType {
     NewGuard(u16), // id
     WakesUp,
     FallsAsleep,
 }
+/// A line of the guards' log, such as `[1518-11-01 00:05] falls asleep`.
 #[derive(PartialEq, Debug)]
-struct Record {
-    record_type: RecordType,
-    date: NaiveDateTime,
+pub struct Record {
+    pub record_type: RecordType,
+    pub date: NaiveDateTime,
 }
 
-fn parse_record<'a>(input: &'a str) -> Record {
-    let input: Vec<&str> = input.split(|c| c == '[' || c == ']').collect();
-    let date = NaiveDateTime::parse_from_str(input[1], "%Y-%m-%d %H:%M").unwrap();
-    let content = input[2].trim();
+/// Parses a line of the log such as `[1518-11-01 00:00] Guard #10 begins shift`.
+pub fn parse_record(input: &str) -> Result<Record> {
+    let line = input.trim();
+    if !line.starts_with('[') {
+        return Err(Error::parse(input, line, RECORD));
+    }
+    let date_end = line
+        .find(']')
+        .ok_or_else(|| Error::missing(input, RECORD))?;
+    let date_field = &line[1..date_end];
+    let date = NaiveDateTime::parse_from_str(date_field, "%Y-%m-%d %H:%M")
+        .map_err(|_| Error::parse(input, date_field, "a date such as `1518-11-01 00:05`"))?;
+    let content = line[date_end + 1..].trim();
     if content == "falls asleep" {
-        Record {
+        Ok(Record {
             record_type: RecordType::FallsAsleep,
-            date: date,
-        }
+            date,
+        })
     } else if content == "wakes up" {
-        Record {
+        Ok(Record {
             record_type: RecordType::WakesUp,
-            date: date,
-        }
-    } else {
+            date,
+        })
+    } else if content.starts_with("Guard ") {
         let (_, id) = content
             .split_whitespace()
             .find(|s| s.starts_with('#'))
-            .unwrap()
+            .ok_or_else(|| Error::parse(input, content, "a guard id such as `#10`"))?
             .split_at(1);
-        Record {
-            record_type: RecordType::NewGuard(id.parse().unwrap()),
-            date: date,
-        }
+        Ok(Record {
+            record_type: RecordType::NewGuard(parse_field(input, id, "a guard id such as `#10`")?),
+            date,
+        })
+    } else {
+        Err(Error::parse(
+            input,
+            content,
+            "`falls asleep`, `wakes up` or `Guard #<id> begins shift`",
+        ))
     }
 }
 
-type GuardPresence = HashMap<NaiveDateTime, bool>;
-type Presences = HashMap<u16, GuardPresence>;
+/// The minutes a guard spent asleep.
+pub type GuardPresence = HashMap<NaiveDateTime, bool>;
+/// The minutes spent asleep by every guard, by guard id.
+pub type Presences = HashMap<u16, GuardPresence>;
 
-fn rec_get_guards_presence<'a>(
-    mut records: Vec<Record>,
-    mut presences: Presences,
-    guard_id: Option<u16>,
-    asleep_date: Option<NaiveDateTime>,
-) -> Presences {
-    let mut current_guard_id = guard_id;
-    let mut current_asleep_date = asleep_date;
-    let map = HashMap::new();
+/// Replays the records, sorted by date, one after the other.
+fn get_guards_presence(records: Vec<Record>) -> Result<Presences> {
+    let mut presences: Presences = HashMap::new();
+    let mut current_guard_id = None;
+    let mut current_asleep_date = None;
 
-    match records.pop() {
-        Some(record) => {
-            match record.record_type {
-                RecordType::NewGuard(id) => {
-                    current_guard_id = Some(id);
-                    presences.entry(id).or_insert(map);
-                }
-                RecordType::FallsAsleep => {
-                    current_asleep_date = Some(record.date);
-                }
-                RecordType::WakesUp => {
-                    let guard_id = current_guard_id.expect("There should be a guard id");
-                    let guard_presence = presences
-                        .get_mut(&guard_id)
-                        .expect("The guard should be present");
-                    let mut asleep_date = current_asleep_date.expect("The guard should be asleep");
-                    current_asleep_date = None;
-                    while asleep_date < record.date {
-                        guard_presence.insert(asleep_date, true);
-                        asleep_date += Duration::minutes(1);
-                    }
+    for record in records {
+        match record.record_type {
+            RecordType::NewGuard(id) => {
+                current_guard_id = Some(id);
+                presences.entry(id).or_default();
+            }
+            RecordType::FallsAsleep => {
+                current_asleep_date = Some(record.date);
+            }
+            RecordType::WakesUp => {
+                let guard_id = current_guard_id.ok_or_else(|| {
+                    Error::Invalid(format!(
+                        "someone wakes up at {} before any guard begins a shift",
+                        record.date
+                    ))
+                })?;
+                let guard_presence = presences
+                    .get_mut(&guard_id)
+                    .expect("The guard should be present");
+                let mut asleep_date = current_asleep_date.take().ok_or_else(|| {
+                    Error::Invalid(format!(
+                        "guard #{} wakes up at {} without falling asleep",
+                        guard_id, record.date
+                    ))
+                })?;
+                while asleep_date < record.date {
+                    guard_presence.insert(asleep_date, true);
+                    asleep_date += Duration::minutes(1);
                 }
             }
-            return rec_get_guards_presence(
-                records,
-                presences,
-                current_guard_id,
-                current_asleep_date,
-            );
-        }
-        None => {
-            return presences;
         }
     }
+    return Ok(presences);
 }
 
-fn get_presence_from_input(input: &str) -> Presences {
-    let presences: Presences = HashMap::new();
-    let mut records: Vec<Record> = input.trim().lines().map(parse_record).collect();
+/// Parses the log, in any order, into the minutes each guard spent asleep.
+pub fn get_presence_from_input(input: &str) -> Result<Presences> {
+    let mut records = input
+        .lines()
+        .enumerate()
+        .filter(|&(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| parse_record(line).map_err(|error| error.on_line(index + 1)))
+        .collect::<Result<Vec<Record>>>()?;
     records.sort_by_key(|record| record.date);
-    records.reverse();
 
-    rec_get_guards_presence(records, presences, None, None)
+    get_guards_presence(records)
 }
 
 fn get_sleepiest_guard_minute(guard_presence: &GuardPresence) -> u32 {
-    let mut minutes_slept: HashMap<u32, u8> = HashMap::new();
+    let mut minutes_slept: HashMap<u32, usize> = HashMap::new();
     let mut sleepiest_minute = (0, 0);
     for &date_time in guard_presence.keys() {
         let minute_count = minutes_slept.entry(date_time.minute()).or_insert(0);
@@ -113,9 +135,9 @@ fn get_sleepiest_guard_minute(guard_presence: &GuardPresence) -> u32 {
     return sleepiest_minute.0;
 }
 
-fn get_sleepiest_guards_minute(input: &str) -> u32 {
-    let presence = get_presence_from_input(input);
-    let guards_asleep_time: HashMap<u16, u16> =
+/// Multiplies the id of the guard sleeping the most by the minute they are most often asleep.
+pub fn get_sleepiest_guards_minute(presence: &Presences) -> Result<u32> {
+    let guards_asleep_time: HashMap<u16, usize> =
         presence.keys().fold(HashMap::new(), |mut map, id| {
             let guard_presence = presence.get(id).unwrap();
             map.insert(
@@ -144,12 +166,15 @@ fn get_sleepiest_guards_minute(input: &str) -> u32 {
         },
     );
 
-    let sleepiest_minute = get_sleepiest_guard_minute(presence.get(&sleepy_guard_id).unwrap());
-    return sleepiest_minute * u32::from(sleepy_guard_id);
+    let sleepy_guard_presence = presence
+        .get(&sleepy_guard_id)
+        .ok_or(Error::NoAnswer("no guard ever falls asleep"))?;
+    let sleepiest_minute = get_sleepiest_guard_minute(sleepy_guard_presence);
+    return Ok(sleepiest_minute * u32::from(sleepy_guard_id));
 }
 
-fn get_most_likely_alseep_minute(input: &str) -> u32 {
-    let presence = get_presence_from_input(input);
+/// Multiplies the id of the guard most frequently asleep on the same minute by that minute.
+pub fn get_most_likely_asleep_minute(presence: &Presences) -> u32 {
     let minute = presence
         .iter()
         .fold(
@@ -173,14 +198,35 @@ fn get_most_likely_alseep_minute(input: &str) -> u32 {
     return u32::from(minute.0) * minute.1;
 }
 
-pub fn exercise_4_1() -> u32 {
-    let content = fs::read_to_string("./inputs/input4").unwrap();
-    return get_sleepiest_guards_minute(&content);
+/// Answer for the first strategy over the log in `input`.
+pub fn exercise_4_1(input: &Input) -> Result<u32> {
+    return Exercise4::solve_part_1(input);
 }
 
-pub fn exercise_4_2() -> u32 {
-    let content = fs::read_to_string("./inputs/input4").unwrap();
-    return get_most_likely_alseep_minute(&content);
+/// Answer for the second strategy over the log in `input`.
+pub fn exercise_4_2(input: &Input) -> Result<u32> {
+    return Exercise4::solve_part_2(input);
+}
+
+/// Day 4: Repose Record.
+pub struct Exercise4;
+
+impl Solution for Exercise4 {
+    type Parsed<'a> = Presences;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(content: &str) -> Result<Presences> {
+        get_presence_from_input(content)
+    }
+
+    fn part_1(presence: &Presences) -> Result<u32> {
+        get_sleepiest_guards_minute(presence)
+    }
+
+    fn part_2(presence: &Presences) -> Result<u32> {
+        Ok(get_most_likely_asleep_minute(presence))
+    }
 }
 
 #[cfg(test)]
@@ -189,12 +235,12 @@ mod test {
 
     #[test]
     fn solve_exercise_4_1() {
-        assert_eq!(exercise_4_1(), 4716);
+        assert_eq!(exercise_4_1(&Input::bundled(4)).unwrap(), 4716);
     }
 
     #[test]
     fn solve_exercise_4_2() {
-        assert_eq!(exercise_4_2(), 117061);
+        assert_eq!(exercise_4_2(&Input::bundled(4)).unwrap(), 117061);
     }
 
     #[test]
@@ -217,7 +263,10 @@ mod test {
         [1518-11-05 00:45] falls asleep
         [1518-11-05 00:55] wakes up";
 
-        assert_eq!(get_most_likely_alseep_minute(example), 4455);
+        assert_eq!(
+            get_most_likely_asleep_minute(&get_presence_from_input(example).unwrap()),
+            4455
+        );
     }
 
     #[test]
@@ -240,31 +289,92 @@ mod test {
         [1518-11-05 00:45] falls asleep
         [1518-11-05 00:55] wakes up";
 
-        assert_eq!(get_sleepiest_guards_minute(example), 240);
+        assert_eq!(
+            get_sleepiest_guards_minute(&get_presence_from_input(example).unwrap()).unwrap(),
+            240
+        );
     }
 
     #[test]
     fn test_parse_record() {
         assert_eq!(
-            parse_record("[1518-11-01 00:00] Guard #10 begins shift"),
+            parse_record("[1518-11-01 00:00] Guard #10 begins shift").unwrap(),
             Record {
                 record_type: RecordType::NewGuard(10),
                 date: "1518-11-01T00:00:00".parse::<NaiveDateTime>().unwrap()
             }
         );
         assert_eq!(
-            parse_record("[1518-11-01 00:05] falls asleep"),
+            parse_record("[1518-11-01 00:05] falls asleep").unwrap(),
             Record {
                 record_type: RecordType::FallsAsleep,
                 date: "1518-11-01T00:05:00".parse::<NaiveDateTime>().unwrap()
             }
         );
         assert_eq!(
-            parse_record("[1518-11-05 00:55] wakes up"),
+            parse_record("[1518-11-05 00:55] wakes up").unwrap(),
             Record {
                 record_type: RecordType::WakesUp,
                 date: "1518-11-05T00:55:00".parse::<NaiveDateTime>().unwrap()
             }
         );
     }
+
+    #[test]
+    fn test_parse_invalid_records() {
+        let positions = |content: &str| match get_presence_from_input(content) {
+            Err(Error::Parse(error)) => (error.line, error.column, error.text),
+            result => panic!("unexpected result {:?}", result),
+        };
+        assert_eq!(
+            (2, 2, String::from("1518-11-01 00:5x")),
+            positions("[1518-11-01 00:00] Guard #10 begins shift\n[1518-11-01 00:5x] falls asleep")
+        );
+        assert_eq!(
+            (1, 20, String::from("dozes off")),
+            positions("[1518-11-01 00:05] dozes off")
+        );
+        assert_eq!(
+            (1, 27, String::from("1x")),
+            positions("[1518-11-01 00:00] Guard #1x begins shift")
+        );
+        assert_eq!(
+            (1, 1, String::from("1518-11-01 00:05 falls asleep")),
+            positions("1518-11-01 00:05 falls asleep")
+        );
+    }
+
+    #[test]
+    fn test_long_log() {
+        // A single guard naps through the hour every other day, and on minute
+        // 30 every day, far more than a u16 worth of minutes.
+        let first_day = NaiveDate::from_ymd_opt(1518, 1, 1).unwrap();
+        let mut log = String::new();
+        for day in 0..3000 {
+            let date = (first_day + Duration::days(day)).format("%Y-%m-%d");
+            let (asleep, awake) = if day % 2 == 0 { (1, 59) } else { (30, 31) };
+            log.push_str(&format!("[{} 00:00] Guard #10 begins shift\n", date));
+            log.push_str(&format!("[{} 00:{:02}] falls asleep\n", date, asleep));
+            log.push_str(&format!("[{} 00:{:02}] wakes up\n", date, awake));
+        }
+        let presences = Exercise4::parse(&log).unwrap();
+        assert_eq!(presences[&10].len(), 1500 * 58 + 1500);
+        assert_eq!(Exercise4::part_1(&presences).unwrap(), 300);
+    }
+
+    #[test]
+    fn test_inconsistent_records() {
+        match get_presence_from_input(
+            "[1518-11-01 00:05] falls asleep\n[1518-11-01 00:25] wakes up",
+        ) {
+            Err(Error::Invalid(_)) => (),
+            result => panic!("unexpected result {:?}", result),
+        }
+        match get_presence_from_input(
+            "[1518-11-01 00:00] Guard #10 begins shift\n[1518-11-01 00:25] wakes up",
+        ) {
+            Err(Error::Invalid(_)) => (),
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
 }
@@ -1,8 +1,28 @@
+use error::{Error, Result};
+use input::Input;
+use solution::Solution;
 use std::collections::LinkedList;
-use std::fs;
+use std::fmt;
 use std::iter::FromIterator;
 
-fn react_polymer_from_string(polymer: &str) -> LinkedList<char> {
+/// Trims the input and checks it only contains polymer units.
+pub fn parse_polymer(content: &str) -> Result<&str> {
+    let polymer = content.trim();
+    for (index, unit) in polymer.char_indices() {
+        if !unit.is_ascii_alphabetic() {
+            let unit = &polymer[index..index + unit.len_utf8()];
+            return Err(Error::parse(
+                content,
+                unit,
+                "a polymer unit between a and Z",
+            ));
+        }
+    }
+    return Ok(polymer);
+}
+
+/// Returns the units left once the polymer fully reacted.
+pub fn react_polymer_from_string(polymer: &str) -> LinkedList<char> {
     let polymer: LinkedList<char> = LinkedList::from_iter(polymer.chars());
     return polymer_react(polymer);
 }
@@ -12,9 +32,7 @@ fn polymer_react_once(mut polymer: LinkedList<char>) -> LinkedList<char> {
     while let Some(unit) = polymer.pop_front() {
         match polymer.front() {
             Some(&first_unit) => {
-                if first_unit != unit
-                    && first_unit.to_ascii_lowercase() == unit.to_ascii_lowercase()
-                {
+                if first_unit != unit && first_unit.eq_ignore_ascii_case(&unit) {
                     polymer.pop_front();
                     reacted_polymer.append(&mut polymer);
                     return reacted_polymer;
@@ -31,7 +49,8 @@ fn polymer_react_once(mut polymer: LinkedList<char>) -> LinkedList<char> {
     return reacted_polymer;
 }
 
-fn polymer_react(mut polymer: LinkedList<char>) -> LinkedList<char> {
+/// Removes reacting pairs of units until the polymer is stable.
+pub fn polymer_react(mut polymer: LinkedList<char>) -> LinkedList<char> {
     let mut initial_length = polymer.len();
     loop {
         let polymer_after_reaction = polymer_react_once(polymer);
@@ -44,34 +63,91 @@ fn polymer_react(mut polymer: LinkedList<char>) -> LinkedList<char> {
     }
 }
 
-pub fn exercise_5_1() -> usize {
-    let content = fs::read_to_string("./inputs/input5").unwrap();
-    let polymer = react_polymer_from_string(content.trim());
-    return polymer.len();
+/// Reacts the polymer in a single pass: each unit either reacts with the last
+/// unit kept so far, or is kept itself.
+pub fn reduce_polymer<I: IntoIterator<Item = char>>(polymer: I) -> Vec<char> {
+    let mut reduced: Vec<char> = Vec::new();
+    for unit in polymer {
+        match reduced.last() {
+            Some(&last_unit) if last_unit != unit && last_unit.eq_ignore_ascii_case(&unit) => {
+                reduced.pop();
+            }
+            _ => reduced.push(unit),
+        }
+    }
+    return reduced;
+}
+
+/// Length of the polymer in `input` once fully reacted.
+pub fn exercise_5_1(input: &Input) -> Result<usize> {
+    return Exercise5::solve_part_1(input);
 }
 
-fn remove_unit_and_react_polymer(polymer: &str, unit_to_remove: char) -> usize {
+/// Returns the length of the stable polymer once every `unit_to_remove` is removed.
+pub fn remove_unit_and_react_polymer(polymer: &str, unit_to_remove: char) -> usize {
     let filtered_polymer = polymer
         .chars()
-        .filter(|unit| unit.to_ascii_lowercase() != unit_to_remove.to_ascii_lowercase());
-    polymer_react(LinkedList::from_iter(filtered_polymer)).len()
+        .filter(|unit| !unit.eq_ignore_ascii_case(&unit_to_remove));
+    reduce_polymer(filtered_polymer).len()
 }
 
-pub fn exercise_5_2() -> usize {
-    let content = fs::read_to_string("./inputs/input5").unwrap();
-    let content = content.trim();
-    let alphabet = vec![
-        'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r',
-        's', 't', 'u', 'v', 'w', 'x', 'y', 'z',
-    ];
-    alphabet.iter().fold(std::usize::MAX, |smallest, &unit| {
-        let polymer_length = remove_unit_and_react_polymer(content, unit);
-        if polymer_length < smallest {
-            polymer_length
-        } else {
-            smallest
-        }
-    })
+/// The shortest stable polymer obtained by removing a single unit type.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShortestPolymer {
+    /// The removed unit type, in lower case.
+    pub removed_unit: char,
+    pub length: usize,
+}
+
+/// Shows the length alone, which is the puzzle's answer; `aoc run` reports the
+/// removed unit separately.
+impl fmt::Display for ShortestPolymer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.length)
+    }
+}
+
+/// Finds the unit type whose removal leaves the shortest stable polymer, the
+/// first one in alphabetical order on ties.
+pub fn find_shortest_polymer(polymer: &str) -> ShortestPolymer {
+    let shortest = (b'a'..=b'z')
+        .map(|unit| {
+            let removed_unit = char::from(unit);
+            ShortestPolymer {
+                removed_unit,
+                length: remove_unit_and_react_polymer(polymer, removed_unit),
+            }
+        })
+        .min_by_key(|shortest| shortest.length)
+        .expect("the alphabet isn't empty");
+    return shortest;
+}
+
+/// The unit type to remove from the polymer in `input` to get the shortest
+/// stable polymer, along with that polymer's length.
+pub fn exercise_5_2(input: &Input) -> Result<ShortestPolymer> {
+    return Exercise5::solve_part_2(input);
+}
+
+/// Day 5: Alchemical Reduction.
+pub struct Exercise5;
+
+impl Solution for Exercise5 {
+    type Parsed<'a> = &'a str;
+    type Answer1 = usize;
+    type Answer2 = ShortestPolymer;
+
+    fn parse(content: &str) -> Result<&str> {
+        parse_polymer(content)
+    }
+
+    fn part_1(polymer: &&str) -> Result<usize> {
+        Ok(reduce_polymer(polymer.chars()).len())
+    }
+
+    fn part_2(polymer: &&str) -> Result<ShortestPolymer> {
+        Ok(find_shortest_polymer(polymer))
+    }
 }
 
 #[cfg(test)]
@@ -80,12 +156,49 @@ mod test {
 
     #[test]
     fn solve_exercise_5_1() {
-        assert_eq!(exercise_5_1(), 9822);
+        assert_eq!(exercise_5_1(&Input::bundled(5)).unwrap(), 9822);
     }
 
     #[test]
     fn solve_exercise_5_2() {
-        assert_eq!(exercise_5_2(), 9822);
+        assert_eq!(
+            exercise_5_2(&Input::bundled(5)).unwrap(),
+            ShortestPolymer {
+                removed_unit: 's',
+                length: 5726
+            }
+        );
+    }
+
+    #[test]
+    fn removing_a_unit_never_lengthens_the_polymer() {
+        let reacted = exercise_5_1(&Input::bundled(5)).unwrap();
+        let shortest = exercise_5_2(&Input::bundled(5)).unwrap();
+        assert!(shortest.length <= reacted);
+    }
+
+    #[test]
+    fn shortest_example_polymer() {
+        let shortest = find_shortest_polymer("dabAcCaCBAcCcaDA");
+        assert_eq!(
+            shortest,
+            ShortestPolymer {
+                removed_unit: 'c',
+                length: 4
+            }
+        );
+        assert_eq!(shortest.to_string(), "4");
+    }
+
+    #[test]
+    fn parse_invalid_polymer() {
+        assert_eq!("dabAcC", parse_polymer("dabAcC\n").unwrap());
+        match parse_polymer("dab Ac") {
+            Err(Error::Parse(error)) => {
+                assert_eq!((1, 4, " "), (error.line, error.column, &*error.text))
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
     }
 
     #[test]
@@ -96,6 +209,48 @@ mod test {
         assert_eq!(remove_unit_and_react_polymer("dabAcCaCBAcCcaDA", 'd'), 6);
     }
 
+    #[test]
+    fn test_reduce_polymer() {
+        assert_eq!(reduce_polymer("abBA".chars()), vec![]);
+        assert_eq!(
+            reduce_polymer("dabAcCaCBAcCcaDA".chars()),
+            vec!['d', 'a', 'b', 'C', 'B', 'A', 'c', 'a', 'D', 'A']
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn reduce_polymer_matches_pair_elimination(polymer in "[a-cA-C]{0,200}") {
+            let reacted: Vec<char> = react_polymer_from_string(&polymer).into_iter().collect();
+            prop_assert_eq!(reduce_polymer(polymer.chars()), reacted);
+        }
+
+        #[test]
+        fn shortest_polymer_matches_pair_elimination(polymer in "[a-dA-D]{0,100}") {
+            let shortest = "abcd"
+                .chars()
+                .map(|unit_to_remove| {
+                    let filtered = polymer
+                        .chars()
+                        .filter(|unit| !unit.eq_ignore_ascii_case(&unit_to_remove));
+                    polymer_react(LinkedList::from_iter(filtered)).len()
+                })
+                .min()
+                .unwrap();
+            prop_assert_eq!(find_shortest_polymer(&polymer).length, shortest);
+        }
+
+        #[test]
+        fn shortest_polymer_not_longer_than_reacted(polymer in "[a-eA-E]{0,200}") {
+            let shortest = find_shortest_polymer(&polymer);
+            prop_assert!(shortest.length <= reduce_polymer(polymer.chars()).len());
+            prop_assert_eq!(
+                shortest.length,
+                remove_unit_and_react_polymer(&polymer, shortest.removed_unit)
+            );
+        }
+    }
+
     #[test]
     fn test_react_polymer() {
         assert_eq!(react_polymer_from_string("aA"), LinkedList::new());
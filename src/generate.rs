@@ -0,0 +1,457 @@
+use chrono::{Duration, NaiveDate};
+use std::collections::BTreeSet;
+
+/// A small SplitMix64 generator, so a seed produces the same inputs on every
+/// platform and with every version of the crate.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        return z ^ (z >> 31);
+    }
+
+    /// A number in `0..bound`, `bound` being strictly positive.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+
+    /// A number in `low..=high`.
+    pub fn between(&mut self, low: i64, high: i64) -> i64 {
+        low + self.below((high - low) as u64 + 1) as i64
+    }
+
+    pub fn chance(&mut self, probability: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < probability
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for index in (1..items.len()).rev() {
+            let other = self.below(index as u64 + 1) as usize;
+            items.swap(index, other);
+        }
+    }
+}
+
+/// Day 1: `count` frequency changes between `-max_change` and `max_change`.
+///
+/// The last change is picked so that the drift over a cycle is smaller than
+/// `count`, which guarantees some frequency is eventually reached twice.
+pub fn frequencies(rng: &mut Rng, count: usize, max_change: i32) -> Option<String> {
+    if count == 0 || max_change < 0 {
+        return None;
+    }
+    let max_change = i64::from(max_change);
+    let mut changes: Vec<i64> = (1..count)
+        .map(|_| rng.between(-max_change, max_change))
+        .collect();
+    let drift = rng.between(1 - count as i64, count as i64 - 1);
+    changes.push(drift - changes.iter().sum::<i64>());
+
+    let lines: Vec<String> = changes
+        .iter()
+        .map(|change| format!("{:+}", change))
+        .collect();
+    return Some(lines.join("\n") + "\n");
+}
+
+fn hamming_distance(first: &[u8], second: &[u8]) -> usize {
+    first
+        .iter()
+        .zip(second)
+        .filter(|&(first, second)| first != second)
+        .count()
+}
+
+fn random_letter(rng: &mut Rng) -> u8 {
+    b'a' + rng.below(26) as u8
+}
+
+/// Day 2: `count` box IDs of `length` lowercase letters, where exactly one
+/// pair of IDs differs by a single letter and every other pair by two or more.
+pub fn box_ids(rng: &mut Rng, count: usize, length: usize) -> Option<String> {
+    if count < 2 || length < 2 {
+        return None;
+    }
+    let max_attempts = count * 100;
+    let mut ids: Vec<Vec<u8>> = Vec::new();
+    let mut attempts = 0;
+    while ids.len() < count - 1 {
+        attempts += 1;
+        if attempts > max_attempts {
+            return None;
+        }
+        let id: Vec<u8> = (0..length).map(|_| random_letter(rng)).collect();
+        if ids.iter().all(|other| hamming_distance(&id, other) >= 2) {
+            ids.push(id);
+        }
+    }
+
+    let original = rng.below(ids.len() as u64) as usize;
+    for _ in 0..max_attempts {
+        let mut twin = ids[original].clone();
+        let position = rng.below(length as u64) as usize;
+        let letter = random_letter(rng);
+        if letter == twin[position] {
+            continue;
+        }
+        twin[position] = letter;
+        let is_unique = ids
+            .iter()
+            .enumerate()
+            .all(|(index, other)| index == original || hamming_distance(&twin, other) >= 2);
+        if is_unique {
+            let position = rng.below(ids.len() as u64 + 1) as usize;
+            ids.insert(position, twin);
+            let lines: Vec<String> = ids
+                .into_iter()
+                .map(|id| String::from_utf8(id).unwrap())
+                .collect();
+            return Some(lines.join("\n") + "\n");
+        }
+    }
+    return None;
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Rectangle {
+    x: u16,
+    y: u16,
+    width: u16,
+    height: u16,
+}
+
+impl Rectangle {
+    fn overlaps(&self, other: &Rectangle) -> bool {
+        self.x < other.x + other.width
+            && other.x < self.x + self.width
+            && self.y < other.y + other.height
+            && other.y < self.y + self.height
+    }
+}
+
+/// A side and its start, so that the side fits on the fabric and contains `cell`.
+fn random_span(rng: &mut Rng, cell: u16, fabric_size: u16, max_size: u16) -> (u16, u16) {
+    let size = rng.between(1, i64::from(max_size)) as u16;
+    let low = i64::from(cell) - i64::from(size) + 1;
+    let high = i64::from(cell.min(fabric_size - size));
+    return (rng.between(low.max(0), high) as u16, size);
+}
+
+fn random_rectangle_around(
+    rng: &mut Rng,
+    cell: (u16, u16),
+    fabric_size: u16,
+    max_size: u16,
+) -> Rectangle {
+    let (x, width) = random_span(rng, cell.0, fabric_size, max_size);
+    let (y, height) = random_span(rng, cell.1, fabric_size, max_size);
+    Rectangle {
+        x,
+        y,
+        width,
+        height,
+    }
+}
+
+/// Day 3: `count` claims on a `fabric_size` inches wide fabric, each at most
+/// `max_claim_size` inches wide, where exactly one claim overlaps no other.
+///
+/// There's no such input for 2 claims, nor when the fabric is too crowded.
+pub fn claims(
+    rng: &mut Rng,
+    count: usize,
+    fabric_size: u16,
+    max_claim_size: u16,
+) -> Option<String> {
+    if count == 0 || count == 2 || max_claim_size == 0 || max_claim_size > fabric_size {
+        return None;
+    }
+    let random_cell = |rng: &mut Rng| {
+        let x = rng.below(u64::from(fabric_size)) as u16;
+        (x, rng.below(u64::from(fabric_size)) as u16)
+    };
+    let intact = {
+        let cell = random_cell(rng);
+        random_rectangle_around(rng, cell, fabric_size, max_claim_size)
+    };
+
+    // Every other claim overlaps a previous one, and the first one is
+    // overlapped by the second.
+    let mut overlapping: Vec<Rectangle> = Vec::new();
+    let mut attempts = 0;
+    while overlapping.len() < count - 1 {
+        attempts += 1;
+        if attempts > count * 100 {
+            return None;
+        }
+        let cell = match overlapping.len() {
+            0 => random_cell(rng),
+            length => {
+                let partner = overlapping[rng.below(length as u64) as usize];
+                let x = partner.x + rng.below(u64::from(partner.width)) as u16;
+                (x, partner.y + rng.below(u64::from(partner.height)) as u16)
+            }
+        };
+        let claim = random_rectangle_around(rng, cell, fabric_size, max_claim_size);
+        if !claim.overlaps(&intact) {
+            overlapping.push(claim);
+        }
+    }
+
+    overlapping.push(intact);
+    rng.shuffle(&mut overlapping);
+    let lines: Vec<String> = overlapping
+        .iter()
+        .enumerate()
+        .map(|(index, claim)| {
+            format!(
+                "#{} @ {},{}: {}x{}",
+                index + 1,
+                claim.x,
+                claim.y,
+                claim.width,
+                claim.height
+            )
+        })
+        .collect();
+    return Some(lines.join("\n") + "\n");
+}
+
+/// Day 4: a shuffled log of `days` shifts taken by `guards` different guards.
+///
+/// Each shift starts shortly before or after midnight and its naps all fit in
+/// the midnight hour, without overlapping.
+pub fn guard_log(rng: &mut Rng, days: usize, guards: usize) -> Option<String> {
+    if days == 0 || guards == 0 || guards > 9999 {
+        return None;
+    }
+    let mut ids = BTreeSet::new();
+    while ids.len() < guards {
+        ids.insert(rng.between(1, 9999));
+    }
+    let mut ids: Vec<i64> = ids.into_iter().collect();
+    rng.shuffle(&mut ids);
+
+    let first_day = NaiveDate::from_ymd_opt(1518, 1, 1).unwrap();
+    let mut lines = Vec::new();
+    for day in 0..days {
+        let date = first_day + Duration::days(day as i64);
+        let guard = ids[rng.below(guards as u64) as usize];
+        let mut first_nap_minute = 0;
+        if rng.chance(0.5) {
+            let eve = date - Duration::days(1);
+            let minute = rng.between(40, 59);
+            lines.push(format!(
+                "[{} 23:{:02}] Guard #{} begins shift",
+                eve.format("%Y-%m-%d"),
+                minute,
+                guard
+            ));
+        } else {
+            let minute = rng.between(0, 5);
+            first_nap_minute = minute + 1;
+            lines.push(format!(
+                "[{} 00:{:02}] Guard #{} begins shift",
+                date.format("%Y-%m-%d"),
+                minute,
+                guard
+            ));
+        }
+
+        // Make sure at least one guard sleeps.
+        let naps = rng.between(if day == 0 { 1 } else { 0 }, 3) as usize;
+        let mut minutes = BTreeSet::new();
+        while minutes.len() < naps * 2 {
+            minutes.insert(rng.between(first_nap_minute, 59));
+        }
+        let minutes: Vec<i64> = minutes.into_iter().collect();
+        for nap in minutes.chunks(2) {
+            let date = date.format("%Y-%m-%d");
+            lines.push(format!("[{} 00:{:02}] falls asleep", date, nap[0]));
+            lines.push(format!("[{} 00:{:02}] wakes up", date, nap[1]));
+        }
+    }
+
+    rng.shuffle(&mut lines);
+    return Some(lines.join("\n") + "\n");
+}
+
+/// Day 5: a polymer of `length` units, where each unit is the reacting
+/// counterpart of the previous one with probability `reactivity`, and a
+/// random unit otherwise.
+pub fn polymer(rng: &mut Rng, length: usize, reactivity: f64) -> Option<String> {
+    if !(0.0..=1.0).contains(&reactivity) {
+        return None;
+    }
+    let mut polymer = String::with_capacity(length + 1);
+    let mut previous: Option<char> = None;
+    for _ in 0..length {
+        let unit = match previous {
+            Some(unit) if rng.chance(reactivity) => {
+                if unit.is_ascii_lowercase() {
+                    unit.to_ascii_uppercase()
+                } else {
+                    unit.to_ascii_lowercase()
+                }
+            }
+            _ => {
+                let unit = random_letter(rng) as char;
+                if rng.chance(0.5) {
+                    unit.to_ascii_uppercase()
+                } else {
+                    unit
+                }
+            }
+        };
+        polymer.push(unit);
+        previous = Some(unit);
+    }
+    polymer.push('\n');
+    return Some(polymer);
+}
+
+/// Settings shared by every day's generator.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+    pub seed: u64,
+    /// How big the input is: changes, IDs, claims, shifts or units depending
+    /// on the day. Defaults to the size of the bundled inputs.
+    pub size: Option<usize>,
+    /// Only used by day 5, see [`polymer`].
+    pub reactivity: f64,
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            seed: 0,
+            size: None,
+            reactivity: 0.5,
+        }
+    }
+}
+
+/// Generates an input for `day`, or `None` when the day doesn't exist or no
+/// valid input matches the options.
+pub fn generate(day: u8, options: &Options) -> Option<String> {
+    let mut rng = Rng::new(options.seed);
+    let size = |default: usize| options.size.unwrap_or(default);
+    match day {
+        1 => frequencies(&mut rng, size(1000), 20),
+        2 => box_ids(&mut rng, size(250), 26),
+        3 => claims(&mut rng, size(1300), 1000, 30),
+        4 => guard_log(&mut rng, size(350), 20),
+        5 => polymer(&mut rng, size(50000), options.reactivity),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ex1::Exercise1;
+    use ex2;
+    use ex3;
+    use ex4::Exercise4;
+    use ex5::Exercise5;
+    use solution::Solution;
+
+    fn options(seed: u64, size: usize) -> Options {
+        Options {
+            seed,
+            size: Some(size),
+            ..Options::default()
+        }
+    }
+
+    #[test]
+    fn same_seed_same_input() {
+        for day in 1..6 {
+            assert_eq!(
+                generate(day, &options(7, 50)),
+                generate(day, &options(7, 50))
+            );
+            assert_ne!(
+                generate(day, &options(7, 50)),
+                generate(day, &options(8, 50))
+            );
+        }
+        assert_eq!(None, generate(6, &Options::default()));
+    }
+
+    #[test]
+    fn frequencies_repeat() {
+        for seed in 0..20 {
+            let content = generate(1, &options(seed, 200)).unwrap();
+            let frequencies = Exercise1::parse(&content).unwrap();
+            assert_eq!(200, frequencies.len());
+            Exercise1::part_2(&frequencies).unwrap();
+        }
+    }
+
+    #[test]
+    fn box_ids_have_a_single_similar_pair() {
+        for seed in 0..20 {
+            let content = generate(2, &options(seed, 60)).unwrap();
+            let ids = ex2::parse_ids(&content);
+            assert_eq!(60, ids.len());
+            let mut similar_pairs = 0;
+            for (index, first) in ids.iter().enumerate() {
+                for second in &ids[index + 1..] {
+                    if ex2::are_ids_similar(first, second).is_some() {
+                        similar_pairs += 1;
+                    }
+                }
+            }
+            assert_eq!(1, similar_pairs);
+        }
+    }
+
+    #[test]
+    fn claims_have_a_single_intact_claim() {
+        for seed in 0..20 {
+            let content = generate(3, &options(seed, 40)).unwrap();
+            let claims = ex3::parse_claims(&content).unwrap();
+            assert_eq!(40, claims.len());
+            let intact_id = ex3::find_claim_with_no_overlap(&claims).unwrap();
+            let others: Vec<_> = claims
+                .into_iter()
+                .filter(|claim| claim.id != intact_id)
+                .collect();
+            assert!(ex3::find_claim_with_no_overlap(&others).is_err());
+        }
+        assert_eq!(None, generate(3, &options(0, 2)));
+    }
+
+    #[test]
+    fn guard_logs_are_consistent() {
+        for seed in 0..20 {
+            let content = generate(4, &options(seed, 30)).unwrap();
+            let presences = Exercise4::parse(&content).unwrap();
+            Exercise4::part_1(&presences).unwrap();
+            Exercise4::part_2(&presences).unwrap();
+        }
+    }
+
+    #[test]
+    fn polymer_reactivity() {
+        let inert = polymer(&mut Rng::new(3), 300, 0.0).unwrap();
+        let reactive = polymer(&mut Rng::new(3), 300, 0.9).unwrap();
+        let reacted_length =
+            |content: &str| Exercise5::part_1(&Exercise5::parse(content).unwrap()).unwrap();
+        assert_eq!(301, inert.len());
+        assert!(reacted_length(&reactive) < reacted_length(&inert));
+        assert_eq!(None, polymer(&mut Rng::new(3), 300, 1.5));
+    }
+}
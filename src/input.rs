@@ -0,0 +1,84 @@
+use std::fs::{self, File};
+use std::io::prelude::*;
+use std::io::{self, BufReader, Cursor};
+use std::path::PathBuf;
+
+/// Where a day's puzzle input comes from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Input {
+    File(PathBuf),
+    Stdin,
+    Text(String),
+}
+
+impl Input {
+    /// The input bundled with the crate for the given day (`inputs/input<day>`).
+    pub fn bundled(day: u8) -> Input {
+        let path = format!("{}/inputs/input{}", env!("CARGO_MANIFEST_DIR"), day);
+        Input::File(PathBuf::from(path))
+    }
+
+    /// Parses a command-line argument, `-` meaning stdin and anything else a file path.
+    pub fn from_arg(arg: &str) -> Input {
+        if arg == "-" {
+            Input::Stdin
+        } else {
+            Input::File(PathBuf::from(arg))
+        }
+    }
+
+    pub fn read(&self) -> io::Result<String> {
+        match *self {
+            Input::File(ref path) => fs::read_to_string(path),
+            Input::Stdin => {
+                let mut content = String::new();
+                io::stdin().read_to_string(&mut content)?;
+                return Ok(content);
+            }
+            Input::Text(ref text) => Ok(text.clone()),
+        }
+    }
+
+    /// Opens the input for reading it piece by piece instead of all at once.
+    pub fn open(&self) -> io::Result<Box<dyn BufRead>> {
+        match *self {
+            Input::File(ref path) => Ok(Box::new(BufReader::new(File::open(path)?))),
+            Input::Stdin => Ok(Box::new(BufReader::new(io::stdin()))),
+            Input::Text(ref text) => Ok(Box::new(Cursor::new(text.clone().into_bytes()))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_text() {
+        let input = Input::Text(String::from("+1\n-2"));
+        assert_eq!("+1\n-2", input.read().unwrap());
+    }
+
+    #[test]
+    fn open_text() {
+        let mut lines = Input::Text(String::from("+1\n-2")).open().unwrap().lines();
+        assert_eq!("+1", lines.next().unwrap().unwrap());
+        assert_eq!("-2", lines.next().unwrap().unwrap());
+        assert!(lines.next().is_none());
+    }
+
+    #[test]
+    fn read_bundled_file() {
+        assert!(Input::bundled(1).read().unwrap().starts_with("+"));
+        assert!(Input::bundled(42).read().is_err());
+    }
+
+    #[test]
+    fn from_arg() {
+        assert_eq!(Input::Stdin, Input::from_arg("-"));
+        assert_eq!(
+            Input::File(PathBuf::from("inputs/input1")),
+            Input::from_arg("inputs/input1")
+        );
+    }
+}
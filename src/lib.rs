@@ -1,7 +1,30 @@
+//! Solutions to the first days of Advent of Code 2018.
+//!
+//! Each `exN` module exposes the types of its puzzle, its parser and solvers,
+//! as well as an `ExerciseN` implementing [`Solution`]. Every day is listed in
+//! [`DAYS`] so it can be run without knowing its answer types.
+
+#![allow(clippy::needless_return)]
+
 extern crate chrono;
+extern crate unicode_segmentation;
+#[cfg(test)]
+#[macro_use]
+extern crate proptest;
+
+pub mod answers;
+pub mod error;
+pub mod generate;
+pub mod input;
+pub mod solution;
+
+pub mod ex1;
+pub mod ex2;
+pub mod ex3;
+pub mod ex4;
+pub mod ex5;
 
-mod ex1;
-mod ex2;
-mod ex3;
-mod ex4;
-mod ex5;
+pub use answers::{Answers, Verdict};
+pub use error::{Error, ParseError, Result};
+pub use input::Input;
+pub use solution::{find_day, Day, Solution, DAYS};
@@ -0,0 +1,250 @@
+#![allow(clippy::needless_return)]
+
+extern crate advent_of_code_2018;
+
+use advent_of_code_2018::answers::{hash_input, Answers, Verdict};
+use advent_of_code_2018::ex1;
+use advent_of_code_2018::ex5::Exercise5;
+use advent_of_code_2018::generate::{self, Options};
+use advent_of_code_2018::input::Input;
+use advent_of_code_2018::solution::{self, Solution, DAYS};
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::str::FromStr;
+use std::time::Instant;
+
+const USAGE: &str = "Usage:
+    aoc run <day> <part> [input]
+    aoc run 1 2 [input] --starts <from>..<to>
+    aoc generate <day> [--seed <n>] [--size <n>] [--reactivity <p>]
+    aoc verify [--inputs <dir>] [--answers <file>]
+    aoc record <day> <part> [input] [--answers <file>]
+
+The input defaults to the bundled inputs/input<day>; pass `-` to read stdin.
+`--starts` lists day 1's first repeat for every start frequency in the range.
+`verify` solves every day against <dir>/input<day> and checks the answers
+recorded in the answers file (the bundled answers.txt by default); `record`
+stores the answer for an input once it has been confirmed.";
+
+fn parse_number<T: FromStr>(value: &str, name: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("Invalid {}: {}", name, value))
+}
+
+/// Removes `--<name> <value>` from the arguments, returning the parsed value.
+fn take_option<T: FromStr>(args: &mut Vec<String>, name: &str) -> Result<Option<T>, String> {
+    let flag = format!("--{}", name);
+    match args.iter().position(|arg| *arg == flag) {
+        Some(index) if index + 1 < args.len() => {
+            let value = args.remove(index + 1);
+            args.remove(index);
+            return parse_number(&value, name).map(Some);
+        }
+        Some(_) => Err(format!("Missing value for {}", flag)),
+        None => Ok(None),
+    }
+}
+
+/// Parses an inclusive `<from>..<to>` range.
+fn parse_range(value: &str, name: &str) -> Result<(i64, i64), String> {
+    let invalid = || format!("Invalid {}: {}", name, value);
+    let mut bounds = value.splitn(2, "..");
+    let from = bounds.next().ok_or_else(invalid)?;
+    let to = bounds.next().ok_or_else(invalid)?;
+    return Ok((parse_number(from, name)?, parse_number(to, name)?));
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    let mut args = args.to_vec();
+    let starts: Option<String> = take_option(&mut args, "starts")?;
+    if args.len() != 2 && args.len() != 3 {
+        return Err(String::from(USAGE));
+    }
+    let day = parse_number(&args[0], "day")?;
+    let part = parse_number(&args[1], "part")?;
+    let input = match args.get(2) {
+        Some(arg) => Input::from_arg(arg),
+        None => Input::bundled(day),
+    };
+    let no_solution = || format!("No solution for day {} part {}", day, part);
+    let solution = solution::find_day(day).ok_or_else(no_solution)?;
+    let mut reader = input
+        .open()
+        .map_err(|error| format!("Could not read input: {}", error))?;
+
+    if let Some(starts) = starts {
+        if (day, part) != (1, 2) {
+            return Err(String::from("--starts only applies to day 1 part 2"));
+        }
+        let (from, to) = parse_range(&starts, "starts")?;
+        let changes = ex1::read_changes(reader)
+            .collect::<advent_of_code_2018::Result<Vec<i32>>>()
+            .map_err(|error| format!("Day 1 part 2 failed: {}", error))?;
+        println!("start\tfirst repeat\tsteps");
+        for row in ex1::first_repeats_from(&changes, from..=to) {
+            let row = row.map_err(|error| format!("Day 1 part 2 failed: {}", error))?;
+            match row.repeat {
+                Some(repeat) => println!("{}\t{}\t{}", row.start, repeat.frequency, repeat.step),
+                None => println!("{}\tnone\t-", row.start),
+            }
+        }
+        return Ok(());
+    }
+
+    let start = Instant::now();
+    let mut removed_unit = None;
+    let answer = if (day, part) == (5, 2) {
+        // Solved directly, to also report which unit was removed.
+        Exercise5::part_2_from_reader(&mut *reader).map(|shortest| {
+            removed_unit = Some(shortest.removed_unit);
+            shortest.to_string()
+        })
+    } else {
+        solution
+            .solve_reader(part, &mut *reader)
+            .ok_or_else(no_solution)?
+    }
+    .map_err(|error| format!("Day {} part {} failed: {}", day, part, error))?;
+    let elapsed = start.elapsed();
+
+    println!("{}", answer);
+    if let Some(unit) = removed_unit {
+        eprintln!("Removing {}/{}", unit, unit.to_ascii_uppercase());
+    }
+    eprintln!("Day {} part {} solved in {:?}", day, part, elapsed);
+    return Ok(());
+}
+
+fn generate(args: &[String]) -> Result<(), String> {
+    let mut args = args.to_vec();
+    let defaults = Options::default();
+    let options = Options {
+        seed: take_option(&mut args, "seed")?.unwrap_or(defaults.seed),
+        size: take_option(&mut args, "size")?,
+        reactivity: take_option(&mut args, "reactivity")?.unwrap_or(defaults.reactivity),
+    };
+    if args.len() != 1 {
+        return Err(String::from(USAGE));
+    }
+    let day = parse_number(&args[0], "day")?;
+
+    let content = generate::generate(day, &options).ok_or_else(|| {
+        format!(
+            "Cannot generate an input for day {} with {:?}",
+            day, options
+        )
+    })?;
+    print!("{}", content);
+    return Ok(());
+}
+
+fn load_answers(path: &Path) -> Result<Answers, String> {
+    return Answers::load(path)
+        .map_err(|error| format!("Could not load {}: {}", path.display(), error));
+}
+
+fn verify(args: &[String]) -> Result<(), String> {
+    let mut args = args.to_vec();
+    let inputs: Option<PathBuf> = take_option(&mut args, "inputs")?;
+    let answers_path = take_option(&mut args, "answers")?.unwrap_or_else(Answers::bundled_path);
+    if !args.is_empty() {
+        return Err(String::from(USAGE));
+    }
+    let answers = load_answers(&answers_path)?;
+
+    let mut failures = 0;
+    for day in DAYS.iter() {
+        let input = match inputs {
+            Some(ref dir) => Input::File(dir.join(format!("input{}", day.number))),
+            None => Input::bundled(day.number),
+        };
+        let content = match input.read() {
+            Ok(content) => content,
+            Err(error) => {
+                println!("Day {}: no input ({})", day.number, error);
+                continue;
+            }
+        };
+        for part in 1..3 {
+            let outcome = answers
+                .verify(day, part, &content)
+                .expect("every day has two parts");
+            match outcome {
+                Ok((answer, verdict)) => {
+                    if let Verdict::Fail { .. } = verdict {
+                        failures += 1;
+                    }
+                    println!("Day {} part {}: {} {}", day.number, part, answer, verdict);
+                }
+                Err(error) => {
+                    failures += 1;
+                    println!("Day {} part {}: FAIL ({})", day.number, part, error);
+                }
+            }
+        }
+    }
+
+    if failures > 0 {
+        return Err(format!("{} answer(s) failed verification", failures));
+    }
+    return Ok(());
+}
+
+fn record(args: &[String]) -> Result<(), String> {
+    let mut args = args.to_vec();
+    let answers_path = take_option(&mut args, "answers")?.unwrap_or_else(Answers::bundled_path);
+    if args.len() != 2 && args.len() != 3 {
+        return Err(String::from(USAGE));
+    }
+    let day = parse_number(&args[0], "day")?;
+    let part = parse_number(&args[1], "part")?;
+    let input = match args.get(2) {
+        Some(arg) => Input::from_arg(arg),
+        None => Input::bundled(day),
+    };
+    let no_solution = || format!("No solution for day {} part {}", day, part);
+    let solution = solution::find_day(day).ok_or_else(no_solution)?;
+    let content = input
+        .read()
+        .map_err(|error| format!("Could not read input: {}", error))?;
+    let answer = solution
+        .solve(part, &content)
+        .ok_or_else(no_solution)?
+        .map_err(|error| format!("Day {} part {} failed: {}", day, part, error))?;
+
+    let mut answers = load_answers(&answers_path)?;
+    if let Some(previous) = answers.record(hash_input(&content), day, part, &answer) {
+        if previous != answer {
+            eprintln!("Replacing previously recorded answer {}", previous);
+        }
+    }
+    answers
+        .save(&answers_path)
+        .map_err(|error| format!("Could not write {}: {}", answers_path.display(), error))?;
+    println!("{}", answer);
+    eprintln!(
+        "Recorded day {} part {} in {}",
+        day,
+        part,
+        answers_path.display()
+    );
+    return Ok(());
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        Some("run") => run(&args[1..]),
+        Some("generate") => generate(&args[1..]),
+        Some("verify") => verify(&args[1..]),
+        Some("record") => record(&args[1..]),
+        _ => Err(String::from(USAGE)),
+    };
+
+    if let Err(message) = result {
+        eprintln!("{}", message);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,148 @@
+use error::Result;
+use ex1::Exercise1;
+use ex2::Exercise2;
+use ex3::Exercise3;
+use ex4::Exercise4;
+use ex5::Exercise5;
+use input::Input;
+use std::fmt::Display;
+use std::io::BufRead;
+
+/// A day's puzzle: how to parse its input and how to solve both of its parts.
+pub trait Solution {
+    type Parsed<'a>;
+    type Answer1: Display;
+    type Answer2: Display;
+
+    fn parse(content: &str) -> Result<Self::Parsed<'_>>;
+    fn part_1(parsed: &Self::Parsed<'_>) -> Result<Self::Answer1>;
+    fn part_2(parsed: &Self::Parsed<'_>) -> Result<Self::Answer2>;
+
+    /// Solves part 1 from a reader. Days that can work on their input as it
+    /// streams in override this instead of reading it whole.
+    fn part_1_from_reader(reader: &mut dyn BufRead) -> Result<Self::Answer1> {
+        let mut content = String::new();
+        reader.read_to_string(&mut content)?;
+        return Self::part_1(&Self::parse(&content)?);
+    }
+
+    /// Solves part 2 from a reader, see `part_1_from_reader`.
+    fn part_2_from_reader(reader: &mut dyn BufRead) -> Result<Self::Answer2> {
+        let mut content = String::new();
+        reader.read_to_string(&mut content)?;
+        return Self::part_2(&Self::parse(&content)?);
+    }
+
+    fn solve_part_1(input: &Input) -> Result<Self::Answer1> {
+        return Self::part_1_from_reader(&mut *input.open()?);
+    }
+
+    fn solve_part_2(input: &Input) -> Result<Self::Answer2> {
+        return Self::part_2_from_reader(&mut *input.open()?);
+    }
+}
+
+fn display_part_1<S: Solution>(content: &str) -> Result<String> {
+    S::part_1(&S::parse(content)?).map(|answer| answer.to_string())
+}
+
+fn display_part_2<S: Solution>(content: &str) -> Result<String> {
+    S::part_2(&S::parse(content)?).map(|answer| answer.to_string())
+}
+
+fn stream_part_1<S: Solution>(reader: &mut dyn BufRead) -> Result<String> {
+    S::part_1_from_reader(reader).map(|answer| answer.to_string())
+}
+
+fn stream_part_2<S: Solution>(reader: &mut dyn BufRead) -> Result<String> {
+    S::part_2_from_reader(reader).map(|answer| answer.to_string())
+}
+
+/// A registered day, with its solution erased so every day can be run the same way.
+pub struct Day {
+    pub number: u8,
+    part_1: fn(&str) -> Result<String>,
+    part_2: fn(&str) -> Result<String>,
+    stream_part_1: fn(&mut dyn BufRead) -> Result<String>,
+    stream_part_2: fn(&mut dyn BufRead) -> Result<String>,
+}
+
+impl Day {
+    pub const fn new<S: Solution>(number: u8) -> Day {
+        Day {
+            number,
+            part_1: display_part_1::<S>,
+            part_2: display_part_2::<S>,
+            stream_part_1: stream_part_1::<S>,
+            stream_part_2: stream_part_2::<S>,
+        }
+    }
+
+    /// Solves `part` (1 or 2) against the raw puzzle input, returning `None`
+    /// for any other part.
+    pub fn solve(&self, part: u8, content: &str) -> Option<Result<String>> {
+        match part {
+            1 => Some((self.part_1)(content)),
+            2 => Some((self.part_2)(content)),
+            _ => None,
+        }
+    }
+
+    /// Like `solve`, reading the puzzle input from `reader` as the day needs it.
+    pub fn solve_reader(&self, part: u8, reader: &mut dyn BufRead) -> Option<Result<String>> {
+        match part {
+            1 => Some((self.stream_part_1)(reader)),
+            2 => Some((self.stream_part_2)(reader)),
+            _ => None,
+        }
+    }
+}
+
+/// Every solved day, in order.
+pub static DAYS: [Day; 5] = [
+    Day::new::<Exercise1>(1),
+    Day::new::<Exercise2>(2),
+    Day::new::<Exercise3>(3),
+    Day::new::<Exercise4>(4),
+    Day::new::<Exercise5>(5),
+];
+
+pub fn find_day(number: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|day| day.number == number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn days_are_registered_in_order() {
+        let numbers: Vec<u8> = DAYS.iter().map(|day| day.number).collect();
+        assert_eq!(vec![1, 2, 3, 4, 5], numbers);
+        assert!(find_day(6).is_none());
+    }
+
+    #[test]
+    fn solve_through_registry() {
+        let day = find_day(1).unwrap();
+        assert_eq!("3", day.solve(1, "+1 -2 +3 +1").unwrap().unwrap());
+        assert_eq!("2", day.solve(2, "+1 -2 +3 +1").unwrap().unwrap());
+        assert!(day.solve(3, "+1 -2 +3 +1").is_none());
+        assert!(day.solve(1, "+1 -2 +x").unwrap().is_err());
+    }
+
+    #[test]
+    fn solve_reader_through_registry() {
+        for day in DAYS.iter() {
+            let content = Input::bundled(day.number).read().unwrap();
+            for part in 1..3 {
+                assert_eq!(
+                    day.solve(part, &content).unwrap().unwrap(),
+                    day.solve_reader(part, &mut content.as_bytes())
+                        .unwrap()
+                        .unwrap()
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,39 @@
+extern crate advent_of_code_2018;
+
+use advent_of_code_2018::ex1;
+use advent_of_code_2018::ex2;
+use advent_of_code_2018::ex3::{self, Claim};
+use advent_of_code_2018::ex4::{self, RecordType};
+use advent_of_code_2018::ex5;
+use advent_of_code_2018::{Error, Input, Solution};
+
+#[test]
+fn day_types_and_parsers_are_public() {
+    let frequencies = ex1::from_string_to_frequencies("+1 -2 +3 +1").unwrap();
+    assert_eq!(3, ex1::generate_frequency(0, frequencies.clone()).unwrap());
+    assert_eq!(Some(2), ex1::find_repeated_frequency(0, frequencies));
+
+    assert_eq!((1, 1), ex2::get_product_checksum("bababc"));
+
+    let claim: Claim = ex3::parse_claim("#123 @ 3,2: 5x4").unwrap();
+    assert_eq!(
+        ("123", (3, 2), (5, 4)),
+        (claim.id, claim.coordinates, claim.size)
+    );
+
+    let record = ex4::parse_record("[1518-11-01 00:00] Guard #10 begins shift").unwrap();
+    assert_eq!(RecordType::NewGuard(10), record.record_type);
+
+    assert_eq!(10, ex5::react_polymer_from_string("dabAcCaCBAcCcaDA").len());
+}
+
+#[test]
+fn solutions_run_on_any_input() {
+    let input = Input::Text(String::from("abcde\nfghij\nfguij\n"));
+    assert_eq!("fgij", ex2::Exercise2::solve_part_2(&input).unwrap());
+
+    match ex3::Exercise3::parse("#1 @ 1,3 4x4") {
+        Err(Error::Parse(error)) => assert_eq!(1, error.line),
+        _ => panic!("the claim should not parse"),
+    }
+}